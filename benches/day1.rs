@@ -0,0 +1,31 @@
+use advent_of_code_2023::day1;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+// Every line contains at least one ASCII digit, so that part 1 does not fail
+const EXAMPLE_INPUT: &str = "\
+9kfpfgzdjdgxjkltdkbkeightmxteightthree
+9bdsbeightjvkrmhdkghfive73four3
+xeightwoninehcrsdbnvtwovtbkhtxktjslsix3
+15fourlgrsk
+two1nine
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day1 example", |b| {
+        b.iter(|| day1::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+
+    let large_input = EXAMPLE_INPUT.repeat(1000);
+    c.bench_function("day1 large", |b| {
+        b.iter(|| day1::solve(black_box(&large_input)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+use advent_of_code_2023::day10;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day10 example", |b| {
+        b.iter(|| day10::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
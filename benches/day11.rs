@@ -0,0 +1,25 @@
+use advent_of_code_2023::day11;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day11 example", |b| {
+        b.iter(|| day11::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
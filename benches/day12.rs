@@ -0,0 +1,21 @@
+use advent_of_code_2023::day12;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day12 example", |b| {
+        b.iter(|| day12::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
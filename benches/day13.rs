@@ -0,0 +1,30 @@
+use advent_of_code_2023::day13;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day13 example", |b| {
+        b.iter(|| day13::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
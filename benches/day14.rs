@@ -0,0 +1,25 @@
+use advent_of_code_2023::day14;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day14 example", |b| {
+        b.iter(|| day14::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
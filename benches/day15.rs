@@ -0,0 +1,14 @@
+use advent_of_code_2023::day15;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day15 example", |b| {
+        b.iter(|| day15::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
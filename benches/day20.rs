@@ -0,0 +1,20 @@
+use advent_of_code_2023::day20;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day20 example", |b| {
+        b.iter(|| day20::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
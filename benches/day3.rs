@@ -0,0 +1,43 @@
+use advent_of_code_2023::day3;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+/// Tiles the example schematic `repetitions` times in both directions.
+fn tiled_example(repetitions: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..repetitions {
+        for line in EXAMPLE_INPUT.lines() {
+            output.push_str(&line.repeat(repetitions));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day3 example", |b| {
+        b.iter(|| day3::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+
+    // 140x140 characters, the same size as the real puzzle input
+    let large_input = tiled_example(14);
+    c.bench_function("day3 large", |b| {
+        b.iter(|| day3::solve(black_box(&large_input)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
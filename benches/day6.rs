@@ -0,0 +1,17 @@
+use advent_of_code_2023::day6;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day6 example", |b| {
+        b.iter(|| day6::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+use advent_of_code_2023::day7;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day7 example", |b| {
+        b.iter(|| day7::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
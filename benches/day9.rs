@@ -0,0 +1,18 @@
+use advent_of_code_2023::day9;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const EXAMPLE_INPUT: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+fn bench_solve(c: &mut Criterion) {
+    c.bench_function("day9 example", |b| {
+        b.iter(|| day9::solve(black_box(EXAMPLE_INPUT)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
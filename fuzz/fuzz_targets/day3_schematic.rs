@@ -0,0 +1,23 @@
+#![no_main]
+
+use advent_of_code_2023::day3::Schematic;
+use libfuzzer_sys::fuzz_target;
+
+// Parsing arbitrary (possibly multi-byte) UTF-8 must never panic. For schematics that parse,
+// the byte ranges of the part numbers must point at their digits in the original line.
+fuzz_target!(|input: &str| {
+    let Ok(schematic) = input.parse::<Schematic>() else {
+        return;
+    };
+
+    let lines = input.lines().collect::<Vec<_>>();
+    for part in schematic.part_numbers.iter().chain(&schematic.non_part_numbers) {
+        let digits = &lines[part.line_idx][part.range_bytes.clone()];
+        assert_eq!(digits.parse::<u64>().unwrap(), part.part_number);
+        assert_eq!(digits.chars().count(), part.range_chars.0.len());
+    }
+    for gear in &schematic.gears {
+        let symbol = lines[gear.line_idx][gear.index_bytes..].chars().next();
+        assert_eq!(symbol, Some(gear.symbol));
+    }
+});
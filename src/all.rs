@@ -0,0 +1,128 @@
+use crate::answer::DayResult;
+use crate::DAYS;
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Directory containing the puzzle inputs, named `day1.txt`, `day2.txt`, etc.
+    /// Days without an input file are skipped.
+    pub dir: PathBuf,
+}
+
+/// Solves one day's puzzle, returning the answers to both parts.
+pub(crate) type DaySolver = fn(&str) -> Result<DayResult>;
+
+pub fn run(args: Args) -> Result<()> {
+    print!("{}", summarize(&args.dir)?);
+    Ok(())
+}
+
+/// Solves every implemented day whose input file (`dayN.txt`) exists in `dir`, and returns
+/// a table of all the answers. Days without an input file are listed as skipped.
+pub fn summarize(dir: &Path) -> Result<String> {
+    let mut table = String::new();
+    // Writing to a `String` cannot fail, hence the `unwrap()`s
+    writeln!(table, "{:>3}  {:>20}  {:>20}", "Day", "Part 1", "Part 2").unwrap();
+
+    for (day, solve) in DAYS {
+        let path = dir.join(format!("day{}.txt", day));
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                warn!("Skipping day {}: {} does not exist", day, path.display());
+                writeln!(table, "{:>3}  skipped (no {})", day, path.display()).unwrap();
+                continue;
+            }
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("While trying to read file {}", path.display()))
+            }
+        };
+
+        let result = solve(&input).with_context(|| format!("While solving day {}", day))?;
+        writeln!(
+            table,
+            "{:>3}  {:>20}  {:>20}",
+            day, result.part1, result.part2
+        )
+        .unwrap();
+    }
+
+    Ok(table)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_summarize_example_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let write =
+            |name: &str, contents: &str| fs::write(dir.path().join(name), contents).unwrap();
+        write("day1.txt", "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n");
+        write(
+            "day2.txt",
+            "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+",
+        );
+        write(
+            "day3.txt",
+            "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+",
+        );
+        write(
+            "day4.txt",
+            "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+",
+        );
+
+        let table = summarize(dir.path()).unwrap();
+        let rows = table.lines().collect::<Vec<_>>();
+        let row =
+            |day: u8, part1: &str, part2: &str| format!("{:>3}  {:>20}  {:>20}", day, part1, part2);
+
+        assert_eq!(rows[1], row(1, "142", "142"));
+        assert_eq!(rows[2], row(2, "8", "2286"));
+        assert_eq!(rows[3], row(3, "4361", "467835"));
+        assert_eq!(rows[4], row(4, "13", "30"));
+        for (row, day) in rows[5..].iter().zip([6, 7, 8, 9]) {
+            assert!(row.starts_with(&format!("{:>3}  skipped", day)), "{}", row);
+        }
+        assert_eq!(rows.len(), 1 + DAYS.len());
+    }
+
+    #[test]
+    fn test_summarize_invalid_input() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("day1.txt"), "no digits here\n").unwrap();
+        let err = summarize(dir.path()).unwrap_err();
+        assert!(format!("{:#}", err).starts_with("While solving day 1"));
+    }
+}
@@ -0,0 +1,103 @@
+//! A common type for the answers of every day, so that they can be handled in the same way
+//! (e.g. printed in a table), no matter whether a day's answers are numbers or text.
+
+use serde_json::Value;
+use std::fmt;
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum Answer {
+    Number(u64),
+    /// For answers that are not (non-negative) numbers
+    Text(String),
+}
+
+/// The answers to both parts of one day's puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DayResult {
+    pub part1: Answer,
+    pub part2: Answer,
+}
+
+impl DayResult {
+    pub fn new(part1: impl Into<Answer>, part2: impl Into<Answer>) -> DayResult {
+        DayResult {
+            part1: part1.into(),
+            part2: part2.into(),
+        }
+    }
+}
+
+/// Displays numbers and text as they are, without quotes. Width and alignment are respected,
+/// e.g. `{:>8}`.
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => fmt::Display::fmt(number, f),
+            Answer::Text(text) => fmt::Display::fmt(text, f),
+        }
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(number: u64) -> Answer {
+        Answer::Number(number)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+/// Numbers become JSON numbers, text becomes a JSON string.
+impl From<Answer> for Value {
+    fn from(answer: Answer) -> Value {
+        match answer {
+            Answer::Number(number) => Value::from(number),
+            Answer::Text(text) => Value::from(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::Number(142).to_string(), "142");
+        assert_eq!(Answer::from("FJK").to_string(), "FJK");
+        assert_eq!(format!("{:>5}", Answer::Number(42)), "   42");
+        assert_eq!(format!("{:>5}", Answer::from("ab")), "   ab");
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(Value::from(Answer::Number(142)), json!(142));
+        assert_eq!(Value::from(Answer::from("FJK")), json!("FJK"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        let result = DayResult::new(142, "FJK");
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(json, r#"{"part1":142,"part2":"FJK"}"#);
+        assert_eq!(serde_json::from_str::<DayResult>(&json).unwrap(), result);
+    }
+}
@@ -10,12 +10,56 @@ pub struct Args {
 
 #[derive(Subcommand, Debug)]
 pub enum Day {
-    /// Run the two algorithms for day 1's challenge
-    Day1(crate::day1::Args),
-    /// Run the two algorithms for day 2's challenge
-    Day2(crate::day2::Args),
-    /// Run the two algorithms for day 3's challenge
-    Day3(crate::day3::Args),
-    /// Run the two algorithms for day 4's challenge
-    Day4(crate::day4::Args),
+    /// Run every implemented day with the inputs from a directory
+    All(crate::all::Args),
+    #[command(flatten)]
+    Solve(crate::DayCommand),
+    /// Print the version, git commit and build time of this binary
+    BuildInfo,
+    /// Download the puzzle input for a day from adventofcode.com
+    #[cfg(feature = "download")]
+    Fetch(crate::fetch::Args),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DayCommand;
+
+    #[test]
+    fn test_every_day_has_a_subcommand() {
+        for (day, _) in crate::DAYS {
+            let subcommand = format!("day{}", day);
+            let args = Args::try_parse_from(["advent-of-code-2023", &subcommand, "input.txt"])
+                .unwrap_or_else(|err| panic!("Failed to parse `{}`: {}", subcommand, err));
+            assert!(
+                matches!(args.day, Day::Solve(_)),
+                "`{}` is not a day",
+                subcommand
+            );
+        }
+    }
+
+    #[test]
+    fn test_subcommand_maps_to_its_variant() {
+        let args = Args::try_parse_from(["advent-of-code-2023", "day4", "input.txt"]).unwrap();
+        assert!(matches!(args.day, Day::Solve(DayCommand::Day4(_))));
+    }
+
+    #[test]
+    fn test_input_from_clipboard() {
+        let args = Args::try_parse_from(["advent-of-code-2023", "day1", "--clipboard"]).unwrap();
+        let Day::Solve(DayCommand::Day1(args)) = args.day else {
+            panic!("Expected day 1, got {:?}", args.day);
+        };
+        assert!(args.input_options.clipboard);
+        assert_eq!(args.input, None);
+
+        // Either a file or the clipboard, but not both
+        assert!(
+            Args::try_parse_from(["advent-of-code-2023", "day1", "--clipboard", "input.txt"])
+                .is_err()
+        );
+        assert!(Args::try_parse_from(["advent-of-code-2023", "day1"]).is_err());
+    }
 }
@@ -0,0 +1,39 @@
+use anyhow::Result;
+
+/// The crate version, git commit and build time of this binary, e.g.
+/// `advent-of-code-2023 0.1.0 (commit 1a2b3c4, built at 1701388800 seconds since the Unix epoch)`.
+///
+/// The commit is `unknown` if the crate was not built from a git checkout.
+pub fn build_info() -> String {
+    format!(
+        "{} {} (commit {}, built at {} seconds since the Unix epoch)",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("BUILD_GIT_HASH"),
+        env!("BUILD_TIMESTAMP")
+    )
+}
+
+pub fn run() -> Result<()> {
+    println!("{}", build_info());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_info_contains_version() {
+        let build_info = build_info();
+        assert!(
+            build_info.starts_with(&format!(
+                "advent-of-code-2023 {} ",
+                env!("CARGO_PKG_VERSION")
+            )),
+            "{}",
+            build_info
+        );
+        assert!(build_info.contains("(commit "), "{}", build_info);
+    }
+}
@@ -0,0 +1,74 @@
+/// A small, order-sensitive checksum (64 bit FNV-1a) for comparing puzzle data between runs
+/// and machines.
+///
+/// Unlike [`std::collections::hash_map::DefaultHasher`], the result is guaranteed to be the
+/// same across Rust versions and platforms. This is not a cryptographic hash.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::checksum::Checksum;
+/// let mut checksum = Checksum::new();
+/// checksum.update(b"hello");
+/// assert_eq!(checksum.finish(), 0xa430d84680aabd0b);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checksum(u64);
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl Checksum {
+    pub fn new() -> Checksum {
+        Checksum(FNV_OFFSET_BASIS)
+    }
+
+    /// Feeds more bytes into the checksum.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Feeds a number into the checksum, in little endian byte order.
+    pub fn update_u64(&mut self, value: u64) {
+        self.update(&value.to_le_bytes());
+    }
+
+    /// The checksum over everything fed in so far.
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for Checksum {
+    fn default() -> Checksum {
+        Checksum::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checksum_known_values() {
+        assert_eq!(Checksum::new().finish(), 0xcbf29ce484222325);
+
+        let mut checksum = Checksum::new();
+        checksum.update(b"a");
+        assert_eq!(checksum.finish(), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_checksum_is_order_sensitive() {
+        let mut a = Checksum::new();
+        a.update_u64(1);
+        a.update_u64(2);
+        let mut b = Checksum::new();
+        b.update_u64(2);
+        b.update_u64(1);
+        assert_ne!(a.finish(), b.finish());
+    }
+}
@@ -4,5 +4,46 @@ use std::path::PathBuf;
 pub struct Args {
     /// Input data from the puzzle (messed up "calibration document" data).
     /// Empty lines are ignored.
-    pub input: PathBuf,
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Instead of the sums, print the calibration value of every line, one per line.
+    /// Only ASCII digits are counted, unless `--spelled-out` is given.
+    #[arg(long)]
+    pub values: bool,
+    /// With `--values`, also count spelled-out digits (as in part 2).
+    #[arg(long, requires = "values")]
+    pub spelled_out: bool,
+    /// Fail on lines whose only digits are zeros (like `0abc0`), instead of counting them as 0.
+    #[arg(long)]
+    pub strict: bool,
+    /// Print a checksum over the (part 1) calibration values of all lines, in order.
+    /// Compare it with someone else's to check that you parse the same values.
+    #[arg(long)]
+    pub line_checksum: bool,
+    /// Fail if both parts have the same answer, i.e. counting spelled-out digits made no
+    /// difference. That hints at an unexpected input or a bug in the part 2 logic.
+    #[arg(long)]
+    pub assert_different: bool,
+    /// Fail if any line has a calibration value outside of 11..=99 (in either part), like a
+    /// line with only zeros. Every value should be two digits, so this hints at a bug.
+    #[arg(long)]
+    pub assert_two_digit: bool,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Read the input line by line instead of loading it into memory at once, for huge
+    /// inputs. The file is read once per part. Cannot be combined with the flags that inspect
+    /// every line, or with `--repeat`.
+    #[arg(
+        long,
+        conflicts_with_all = ["values", "line_checksum", "assert_two_digit", "max_line_length", "repeat"]
+    )]
+    pub stream: bool,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
 }
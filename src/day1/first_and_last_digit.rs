@@ -79,6 +79,40 @@ pub fn first_and_last_digit_decimal_or_spelled(input: &str) -> Option<(u8, u8)>
     Some((first_digit, last_digit))
 }
 
+#[cfg(feature = "aho")]
+lazy_static::lazy_static! {
+    /// Matches the nine spelled-out digits (pattern IDs 0 to 8) and the nine ASCII digits
+    /// (pattern IDs 9 to 17).
+    static ref DIGITS_AUTOMATON: aho_corasick::AhoCorasick = aho_corasick::AhoCorasick::new(
+        DIGITS
+            .iter()
+            .map(|(spelled_digit, _)| spelled_digit.to_string())
+            .chain((1..=9).map(|digit: u8| digit.to_string()))
+    )
+    .unwrap();
+}
+
+/// Same as [`first_and_last_digit_decimal_or_spelled`], but scans the input only once using
+/// an Aho-Corasick automaton, instead of searching for each digit separately.
+///
+/// Spelled-out digits may overlap, e.g. `eightwo` contains both 8 and 2, so overlapping
+/// matches are considered as well.
+#[cfg(feature = "aho")]
+pub fn first_and_last_digit_aho(input: &str) -> Option<(u8, u8)> {
+    let digit_of_match = |m: aho_corasick::Match| (m.pattern().as_usize() % DIGITS.len()) as u8 + 1;
+
+    let mut matches = DIGITS_AUTOMATON.find_overlapping_iter(input);
+    let first = matches.next()?;
+    // Overlapping matches are reported by their end position, so the match that
+    // starts last is not necessarily reported last
+    let last = matches.fold(
+        first,
+        |last, m| if m.start() >= last.start() { m } else { last },
+    );
+
+    Some((digit_of_match(first), digit_of_match(last)))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -200,4 +234,97 @@ mod test {
         );
         assert_eq!(first_and_last_digit_decimal_or_spelled("thirteen"), None);
     }
+
+    #[cfg(feature = "aho")]
+    #[test]
+    fn test_aho_same_as_decimal_or_spelled() {
+        let inputs = [
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "xtwone3four",
+            "4nineeightseven2",
+            "4nineeight2seven",
+            "zoneight234",
+            "7pqrstsixteen",
+            "6tvxlgrsevenjvbxbfqrsk4seven",
+            "oneight",
+            "twone",
+            "one",
+            "two",
+            "three",
+            "four",
+            "five",
+            "six",
+            "seven",
+            "eight",
+            "nine",
+            "2",
+            "zero",
+            "0",
+            "",
+            "x",
+            "foobarasdf hello world",
+            "thirteen",
+            "abc12d34ef",
+        ];
+        for input in inputs {
+            assert_eq!(
+                first_and_last_digit_aho(input),
+                first_and_last_digit_decimal_or_spelled(input),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(first_and_last_digit_aho("eightwothree"), Some((8, 3)));
+        assert_eq!(first_and_last_digit_aho("xtwone3four"), Some((2, 4)));
+    }
+
+    /// Random text without any digit from 1 to 9. Zero is not a digit for these algorithms,
+    /// so it may show up anywhere.
+    const FILLER: &str = "[a-z0 ]{0,20}";
+    /// Random text that often contains spelled-out digits, and decimal digits including zero.
+    const DIGITS_AND_WORDS: &str =
+        "(one|two|three|four|five|six|seven|eight|nine|zero|[a-z0-9 ]){0,15}";
+
+    proptest::proptest! {
+        #[test]
+        fn prop_decimal_finds_injected_digits(
+            prefix in FILLER,
+            first in 1..=9u8,
+            middle in "[a-z0-9 ]{0,20}",
+            last in 1..=9u8,
+            suffix in FILLER,
+        ) {
+            let input = format!("{}{}{}{}{}", prefix, first, middle, last, suffix);
+            proptest::prop_assert_eq!(first_and_last_digit_decimal(&input), Some((first, last)));
+        }
+
+        #[test]
+        fn prop_decimal_finds_single_injected_digit(
+            prefix in FILLER,
+            digit in 1..=9u8,
+            suffix in FILLER,
+        ) {
+            let input = format!("{}{}{}", prefix, digit, suffix);
+            proptest::prop_assert_eq!(first_and_last_digit_decimal(&input), Some((digit, digit)));
+            proptest::prop_assert_eq!(first_and_last_digit_decimal(&prefix), None);
+        }
+
+        #[test]
+        fn prop_decimal_or_spelled_never_finds_fewer(input in DIGITS_AND_WORDS) {
+            if first_and_last_digit_decimal(&input).is_some() {
+                proptest::prop_assert!(first_and_last_digit_decimal_or_spelled(&input).is_some());
+            }
+        }
+
+        #[test]
+        fn prop_decimal_or_spelled_same_without_words(input in "[a-d0-9 ]{0,30}") {
+            // None of the letters `a` to `d` appear in a spelled-out digit
+            proptest::prop_assert_eq!(
+                first_and_last_digit_decimal_or_spelled(&input),
+                first_and_last_digit_decimal(&input)
+            );
+        }
+    }
 }
@@ -1,31 +1,197 @@
+#[cfg(feature = "cli")]
 mod args;
 mod first_and_last_digit;
 
+#[cfg(feature = "cli")]
 pub use args::Args;
 pub use first_and_last_digit::*;
 
-use anyhow::{anyhow, Context, Result};
-use std::fs;
+use crate::answer::DayResult;
+use crate::checksum::Checksum;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, log_line_counts, open_input, read_input};
+use crate::parse::numbered_nonempty_lines;
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+use std::io::BufRead;
+use std::ops::RangeInclusive;
 
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of all lines, counting ASCII digits only
+    pub part1: u64,
+    /// Sum of all lines, counting ASCII digits and spelled-out digits
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
 pub fn run(args: Args) -> Result<()> {
-    let input = fs::read_to_string(&args.input)
-        .with_context(|| format!("While trying to read file {}", args.input.display()))?;
+    let zero_only_lines = if args.strict {
+        ZeroOnlyLines::Error
+    } else {
+        ZeroOnlyLines::CountAsZero
+    };
+
+    let answers = if args.stream {
+        // The file is read once per part, one line at a time
+        Answers {
+            part1: sum_first_and_last_digits_reader(
+                open_input(args.input.as_deref(), &args.input_options)?,
+                first_and_last_digit_decimal,
+                zero_only_lines,
+            )?,
+            part2: sum_first_and_last_digits_reader(
+                open_input(args.input.as_deref(), &args.input_options)?,
+                first_and_last_digit_decimal_or_spelled,
+                zero_only_lines,
+            )?,
+        }
+    } else {
+        let input = read_input(args.input.as_deref(), &args.input_options)?;
+        check_line_lengths(&input, args.max_line_length)?;
+        log_line_counts(&input);
+
+        if args.values {
+            let digit_algorithm = if args.spelled_out {
+                first_and_last_digit_decimal_or_spelled
+            } else {
+                first_and_last_digit_decimal
+            };
+            for value in calibration_values(&input, digit_algorithm, zero_only_lines) {
+                println!("{}", value?);
+            }
+            return Ok(());
+        }
+
+        if args.line_checksum {
+            let checksum = line_checksum(&input, zero_only_lines)?;
+            println!("{:016x}", checksum);
+        }
 
-    let sum_decimal = sum_first_and_last_digits(&input, first_and_last_digit_decimal)?;
-    let sum_decimal_or_spelled =
-        sum_first_and_last_digits(&input, first_and_last_digit_decimal_or_spelled)?;
+        if args.assert_two_digit {
+            assert_two_digit(&input, zero_only_lines)?;
+        }
+
+        solve_repeatedly(&input, args.repeat, |input| {
+            solve_with_policy(input, zero_only_lines)
+        })?
+    };
+    if args.assert_different {
+        assert_different(&answers)?;
+    }
     tracing::info!(
         "Sum of all lines (Part 1 - Counting ASCII digits only): {}",
-        sum_decimal
+        answers.part1
     );
     tracing::info!(
         "Sum of all lines (Part 2 - Counting ASCII digits and spelled-out digits): {}",
-        sum_decimal_or_spelled
+        answers.part2
     );
 
     Ok(())
 }
 
+/// What to do with a line whose only digits are zeros, like `0abc0`.
+/// Zeros never count as digits, so such a line has no first and last digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZeroOnlyLines {
+    /// The line contributes a calibration value of 0.
+    #[default]
+    CountAsZero,
+    /// The line is an error, just like a line without any digits.
+    Error,
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+/// Lines with only zeros count as 0, see [`solve_with_policy`].
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day1::{solve, Answers};
+/// let answers = solve("1abc2\ntreb7uchet\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 89, part2: 89 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    solve_with_policy(input, ZeroOnlyLines::default())
+}
+
+/// Like [`solve`], but with a choice of how to handle lines whose only digits are zeros.
+pub fn solve_with_policy(input: &str, zero_only_lines: ZeroOnlyLines) -> Result<Answers> {
+    Ok(Answers {
+        part1: sum_first_and_last_digits(input, first_and_last_digit_decimal, zero_only_lines)?,
+        part2: sum_first_and_last_digits(
+            input,
+            first_and_last_digit_decimal_or_spelled,
+            zero_only_lines,
+        )?,
+    })
+}
+
+/// Fails if both parts have the same answer, which means that counting spelled-out digits
+/// did not change anything.
+pub fn assert_different(answers: &Answers) -> Result<()> {
+    ensure!(
+        answers.part1 != answers.part2,
+        "Both parts have the same answer ({}), so spelled-out digits made no difference",
+        answers.part1
+    );
+    Ok(())
+}
+
+/// Calibration values that consist of two non-zero digits, see [`assert_two_digit`].
+pub const TWO_DIGIT_VALUES: RangeInclusive<u64> = 11..=99;
+
+/// Fails if any non-empty line has a calibration value outside of [`TWO_DIGIT_VALUES`], counting
+/// either ASCII digits only (part 1) or also spelled-out digits (part 2).
+///
+/// Zeros do not count as digits, so every line with a digit should have a two-digit value.
+/// The only exception are lines with only zeros, which count as 0 with
+/// [`ZeroOnlyLines::CountAsZero`].
+pub fn assert_two_digit(input: &str, zero_only_lines: ZeroOnlyLines) -> Result<()> {
+    assert_two_digit_with(input, first_and_last_digit_decimal, zero_only_lines)?;
+    assert_two_digit_with(
+        input,
+        first_and_last_digit_decimal_or_spelled,
+        zero_only_lines,
+    )
+}
+
+fn assert_two_digit_with<F>(
+    input: &str,
+    digit_algorithm: F,
+    zero_only_lines: ZeroOnlyLines,
+) -> Result<()>
+where
+    F: Fn(&str) -> Option<(u8, u8)>,
+{
+    // calibration_values() skips the same empty lines
+    let lines = numbered_nonempty_lines(input);
+    for ((line_no, line), value) in
+        lines.zip(calibration_values(input, digit_algorithm, zero_only_lines))
+    {
+        let value = value?;
+        ensure!(
+            TWO_DIGIT_VALUES.contains(&value),
+            "Line {} (contents: `{}`) has the calibration value {}, expected a two-digit value in {:?}",
+            line_no,
+            line,
+            value,
+            TWO_DIGIT_VALUES
+        );
+    }
+    Ok(())
+}
+
 /// Split the given `input` string into lines. For each line,
 /// run the given `digit_algorithm` to find the first and last digit inside.
 /// The found first and last digit are combined using [`concatenate_digits`].
@@ -33,37 +199,124 @@ pub fn run(args: Args) -> Result<()> {
 /// All such combined digits are then summed up and returned.
 ///
 /// Returns an error if a non-empty line is encountered that does not
-/// contain any digits.
+/// contain any digits. A line that only contains zeros is handled according to
+/// `zero_only_lines`.
 ///
 /// For digit algorithms, see [`first_and_last_digit_decimal`] and
 /// [`first_and_last_digit_decimal_or_spelled`].
-pub fn sum_first_and_last_digits<F>(input: &str, digit_algorithm: F) -> Result<u64>
+pub fn sum_first_and_last_digits<F>(
+    input: &str,
+    digit_algorithm: F,
+    zero_only_lines: ZeroOnlyLines,
+) -> Result<u64>
+where
+    F: Fn(&str) -> Option<(u8, u8)>,
+{
+    calibration_values(input, digit_algorithm, zero_only_lines).sum::<Result<u64>>()
+}
+
+/// Like [`sum_first_and_last_digits`], but yields the combined digits (the "calibration
+/// value") of every non-empty line one by one, instead of summing them up.
+///
+/// Yields an error for a non-empty line that does not contain any digits.
+pub fn calibration_values<'a, F>(
+    input: &'a str,
+    digit_algorithm: F,
+    zero_only_lines: ZeroOnlyLines,
+) -> impl Iterator<Item = Result<u64>> + 'a
+where
+    F: Fn(&str) -> Option<(u8, u8)> + 'a,
+{
+    numbered_nonempty_lines(input).map(move |(line_no, line)| {
+        calibration_value(line_no, line, &digit_algorithm, zero_only_lines)
+    })
+}
+
+/// Like [`sum_first_and_last_digits`], but reads the lines one by one from `reader`, so that
+/// huge inputs never have to be held in memory at once.
+///
+/// Fails with the same errors, or if reading a line fails (e.g. because it is not UTF-8).
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day1::{
+/// #     first_and_last_digit_decimal, sum_first_and_last_digits_reader, ZeroOnlyLines,
+/// # };
+/// let reader = std::io::Cursor::new("1abc2\ntreb7uchet\n");
+/// let sum = sum_first_and_last_digits_reader(
+///     reader,
+///     first_and_last_digit_decimal,
+///     ZeroOnlyLines::CountAsZero,
+/// );
+/// assert_eq!(sum.unwrap(), 89);
+/// ```
+pub fn sum_first_and_last_digits_reader<R, F>(
+    reader: R,
+    digit_algorithm: F,
+    zero_only_lines: ZeroOnlyLines,
+) -> Result<u64>
+where
+    R: BufRead,
+    F: Fn(&str) -> Option<(u8, u8)>,
+{
+    let mut sum = 0;
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line.with_context(|| format!("While trying to read line {}", line_idx + 1))?;
+        if line.is_empty() {
+            continue;
+        }
+        sum += calibration_value(line_idx + 1, &line, &digit_algorithm, zero_only_lines)?;
+    }
+    Ok(sum)
+}
+
+/// The calibration value of a single non-empty line, see [`calibration_values`].
+fn calibration_value<F>(
+    line_no: usize,
+    line: &str,
+    digit_algorithm: F,
+    zero_only_lines: ZeroOnlyLines,
+) -> Result<u64>
 where
     F: Fn(&str) -> Option<(u8, u8)>,
 {
-    input
-        .lines()
-        .enumerate()
-        .filter(|(_, line)| !line.is_empty())
-        .map(|(line_idx, line)| {
-            let (first, last) = digit_algorithm(line).ok_or_else(|| {
-                anyhow!(
-                    "Line {} (contents: `{}`) does not contain any digits",
-                    line_idx + 1,
-                    line
-                )
-            })?;
-            // The first and last digits concatenate, e.g. 4 + 7 = 47
-            let concatenated = concatenate_digits(first, last) as u64;
-            tracing::debug!(
-                "Line {} (contents: `{}`) -> {}",
-                line_idx + 1,
-                line,
-                concatenated,
+    let Some((first, last)) = digit_algorithm(line) else {
+        if line.contains('0') {
+            ensure!(
+                zero_only_lines == ZeroOnlyLines::CountAsZero,
+                "Line {} (contents: `{}`) only contains zeros, which do not count as digits",
+                line_no,
+                line
             );
-            anyhow::Ok(concatenated)
-        })
-        .sum::<Result<u64>>()
+            tracing::debug!("Line {} (contents: `{}`) -> 0", line_no, line);
+            return Ok(0);
+        }
+        bail!(
+            "Line {} (contents: `{}`) does not contain any digits",
+            line_no,
+            line
+        );
+    };
+    // The first and last digits concatenate, e.g. 4 + 7 = 47
+    let concatenated = concatenate_digits(first, last) as u64;
+    tracing::debug!(
+        "Line {} (contents: `{}`) -> {}",
+        line_no,
+        line,
+        concatenated,
+    );
+    Ok(concatenated)
+}
+
+/// Computes a [`Checksum`] over the part 1 [`calibration_values`] of all lines, in order.
+/// Two inputs with the same checksum almost certainly have the same values on every line.
+pub fn line_checksum(input: &str, zero_only_lines: ZeroOnlyLines) -> Result<u64> {
+    let mut checksum = Checksum::new();
+    for value in calibration_values(input, first_and_last_digit_decimal, zero_only_lines) {
+        checksum.update_u64(value?);
+    }
+    Ok(checksum.finish())
 }
 
 /// Concatenates two decimal digits into a single `u8`.
@@ -100,10 +353,11 @@ xeightwoninehcrsdbnvtwovtbkhtxktjslsix3
 5xjqd9
 four8ttpzxpnrqnkz1"#;
 
-        let expected = [99, 93, 33, 15, 59, 81].into_iter().sum();
+        let expected = [99, 93, 33, 15, 59, 81].into_iter().sum::<u64>();
 
         assert_eq!(
-            sum_first_and_last_digits(input, first_and_last_digit_decimal).unwrap(),
+            sum_first_and_last_digits(input, first_and_last_digit_decimal, ZeroOnlyLines::Error)
+                .unwrap(),
             expected
         );
     }
@@ -118,11 +372,16 @@ xtwone3four
 zoneight234
 7pqrstsixteen"#;
 
-        let expected = [29, 83, 13, 24, 42, 14, 76].into_iter().sum();
+        let expected = [29, 83, 13, 24, 42, 14, 76].into_iter().sum::<u64>();
         assert_eq!(expected, 281);
 
         assert_eq!(
-            sum_first_and_last_digits(input, first_and_last_digit_decimal_or_spelled).unwrap(),
+            sum_first_and_last_digits(
+                input,
+                first_and_last_digit_decimal_or_spelled,
+                ZeroOnlyLines::Error
+            )
+            .unwrap(),
             expected
         );
     }
@@ -138,10 +397,11 @@ zoneight234
 5abc9
 1abc3"#;
 
-        let expected = [99, 22, 11, 77, 59, 13].into_iter().sum();
+        let expected = [99, 22, 11, 77, 59, 13].into_iter().sum::<u64>();
 
         assert_eq!(
-            sum_first_and_last_digits(input, first_and_last_digit_decimal).unwrap(),
+            sum_first_and_last_digits(input, first_and_last_digit_decimal, ZeroOnlyLines::Error)
+                .unwrap(),
             expected
         );
     }
@@ -149,7 +409,170 @@ zoneight234
     #[test]
     fn test_lines_without_digits() {
         let input = "abcd";
-        assert!(sum_first_and_last_digits(input, first_and_last_digit_decimal).is_err());
-        assert!(sum_first_and_last_digits(input, first_and_last_digit_decimal_or_spelled).is_err());
+        assert!(sum_first_and_last_digits(
+            input,
+            first_and_last_digit_decimal,
+            ZeroOnlyLines::Error
+        )
+        .is_err());
+        assert!(sum_first_and_last_digits(
+            input,
+            first_and_last_digit_decimal_or_spelled,
+            ZeroOnlyLines::Error
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_calibration_values_decimal() {
+        let input = "1abc2\npqr3stu8vwx\n\na1b2c3d4e5f\ntreb7uchet\n";
+        assert_eq!(
+            calibration_values(input, first_and_last_digit_decimal, ZeroOnlyLines::Error)
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            vec![12, 38, 15, 77]
+        );
+    }
+
+    #[test]
+    fn test_calibration_values_error_has_line_number() {
+        let mut values = calibration_values(
+            "12\nabc\n34",
+            first_and_last_digit_decimal,
+            ZeroOnlyLines::Error,
+        );
+        assert_eq!(values.next().unwrap().unwrap(), 12);
+        let err = values.next().unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 2 (contents: `abc`) does not contain any digits"
+        );
+    }
+
+    #[test]
+    fn test_zero_only_lines() {
+        let input = "0abc0\n5x9";
+
+        assert_eq!(
+            sum_first_and_last_digits(
+                input,
+                first_and_last_digit_decimal,
+                ZeroOnlyLines::CountAsZero
+            )
+            .unwrap(),
+            59
+        );
+        assert_eq!(
+            solve(input).unwrap(),
+            Answers {
+                part1: 59,
+                part2: 59
+            }
+        );
+
+        let err =
+            sum_first_and_last_digits(input, first_and_last_digit_decimal, ZeroOnlyLines::Error)
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 1 (contents: `0abc0`) only contains zeros, which do not count as digits"
+        );
+        assert!(solve_with_policy(input, ZeroOnlyLines::Error).is_err());
+    }
+
+    #[test]
+    fn test_assert_different() {
+        let decimal_only = solve("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n").unwrap();
+        let err = assert_different(&decimal_only).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Both parts have the same answer (142), so spelled-out digits made no difference"
+        );
+
+        // Every line needs an ASCII digit for part 1 to succeed
+        let spelled = solve("two1nine\nabcone2threexyz\nzoneight234\n7pqrstsixteen\n").unwrap();
+        assert!(assert_different(&spelled).is_ok());
+    }
+
+    #[test]
+    fn test_assert_two_digit() {
+        let sample = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+        assert!(assert_two_digit(sample, ZeroOnlyLines::CountAsZero).is_ok());
+
+        // A line with only zeros has the value 0 in both parts
+        let zero = "1abc2\n0xyz0\n";
+        let err = assert_two_digit(zero, ZeroOnlyLines::CountAsZero).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 2 (contents: `0xyz0`) has the calibration value 0, expected a two-digit value in 11..=99"
+        );
+    }
+
+    #[test]
+    fn test_sum_first_and_last_digits_reader() {
+        let input = "1abc2\npqr3stu8vwx\n\na1b2c3d4e5f\ntreb7uchet\r\n";
+        for digit_algorithm in [
+            first_and_last_digit_decimal,
+            first_and_last_digit_decimal_or_spelled,
+        ] {
+            let from_reader = sum_first_and_last_digits_reader(
+                std::io::Cursor::new(input.as_bytes()),
+                digit_algorithm,
+                ZeroOnlyLines::CountAsZero,
+            )
+            .unwrap();
+            let from_str =
+                sum_first_and_last_digits(input, digit_algorithm, ZeroOnlyLines::CountAsZero)
+                    .unwrap();
+            assert_eq!(from_reader, 142);
+            assert_eq!(from_reader, from_str);
+        }
+    }
+
+    #[test]
+    fn test_sum_first_and_last_digits_reader_errors() {
+        let err = sum_first_and_last_digits_reader(
+            std::io::Cursor::new("1abc2\n\nabc\n"),
+            first_and_last_digit_decimal,
+            ZeroOnlyLines::CountAsZero,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 3 (contents: `abc`) does not contain any digits"
+        );
+
+        let err = sum_first_and_last_digits_reader(
+            std::io::Cursor::new(b"1abc2\n\xff\n"),
+            first_and_last_digit_decimal,
+            ZeroOnlyLines::CountAsZero,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "While trying to read line 2");
+    }
+
+    #[test]
+    fn test_line_checksum() {
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+        let checksum = line_checksum(input, ZeroOnlyLines::Error).unwrap();
+        assert_eq!(checksum, 0x11ef0d6aab3491ad);
+
+        // Empty lines and characters other than the first and last digit don't matter
+        let equivalent_input = "1abc2\n\npqr3xx8\na1b5\ntreb7uchet\n";
+        assert_eq!(
+            line_checksum(equivalent_input, ZeroOnlyLines::Error).unwrap(),
+            checksum
+        );
+
+        let changed_input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb8uchet\n";
+        assert_ne!(
+            line_checksum(changed_input, ZeroOnlyLines::Error).unwrap(),
+            checksum
+        );
+        let reordered_input = "pqr3stu8vwx\n1abc2\na1b2c3d4e5f\ntreb7uchet\n";
+        assert_ne!(
+            line_checksum(reordered_input, ZeroOnlyLines::Error).unwrap(),
+            checksum
+        );
     }
 }
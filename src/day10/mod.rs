@@ -0,0 +1,389 @@
+use crate::answer::DayResult;
+use crate::grid::Grid;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+use std::collections::HashSet;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+const START_TILE: char = 'S';
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (a grid of pipes with a single `S` start tile).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Steps along the loop from the start to the farthest point
+    pub part1: u64,
+    /// Number of tiles enclosed by the loop
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!(
+        "(Part 1) Steps to the farthest point of the loop: {}",
+        answers.part1
+    );
+    info!("(Part 2) Tiles enclosed by the loop: {}", answers.part2);
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day10::{solve, Answers};
+/// let answers = solve(".....\n.S-7.\n.|.|.\n.L-J.\n.....\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 4, part2: 1 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let maze = input.parse::<PipeMaze>()?;
+    let loop_tiles = maze.loop_tiles()?;
+
+    Ok(Answers {
+        part1: loop_tiles.len() as u64 / 2,
+        part2: maze.enclosed_tiles(&loop_tiles.into_iter().collect()),
+    })
+}
+
+/// One of the four directions a pipe can connect to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// Offset (row, column) of one step into this direction.
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+}
+
+/// The two directions the given pipe connects, or `None` if it is not a pipe
+/// (like ground `.`).
+fn connections(tile: char) -> Option<[Direction; 2]> {
+    use Direction::*;
+    match tile {
+        '|' => Some([North, South]),
+        '-' => Some([East, West]),
+        'L' => Some([North, East]),
+        'J' => Some([North, West]),
+        '7' => Some([South, West]),
+        'F' => Some([South, East]),
+        _ => None,
+    }
+}
+
+/// The pipe that connects the two given directions, in any order.
+fn pipe_connecting(a: Direction, b: Direction) -> Option<char> {
+    ['|', '-', 'L', 'J', '7', 'F']
+        .into_iter()
+        .find(|&pipe| connections(pipe).is_some_and(|[c, d]| (c, d) == (a, b) || (c, d) == (b, a)))
+}
+
+/// The grid of pipes, with the start tile already replaced by the pipe that fits there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeMaze {
+    grid: Grid<char>,
+    start: (usize, usize),
+}
+
+impl FromStr for PipeMaze {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<PipeMaze> {
+        let mut grid = input.parse::<Grid<char>>()?;
+
+        let starts = grid
+            .iter()
+            .filter(|&(_, _, &tile)| tile == START_TILE)
+            .map(|(row, col, _)| (row, col))
+            .collect::<Vec<_>>();
+        let Some(&(row, col)) = starts.first() else {
+            bail!("The maze does not contain a start tile `{}`", START_TILE);
+        };
+        if let Some(&(other_row, other_col)) = starts.get(1) {
+            bail!(
+                "The maze contains more than one start tile `{}`, on line {}, column {} and line {}, column {}",
+                START_TILE,
+                row + 1,
+                col + 1,
+                other_row + 1,
+                other_col + 1
+            );
+        }
+
+        // The start tile is whatever pipe connects to the neighbouring pipes that connect back
+        let connected = Direction::ALL
+            .into_iter()
+            .filter(|&direction| {
+                let (row_offset, col_offset) = direction.offset();
+                grid.get(row as isize + row_offset, col as isize + col_offset)
+                    .and_then(|&tile| connections(tile))
+                    .is_some_and(|neighbor| neighbor.contains(&direction.opposite()))
+            })
+            .collect::<Vec<_>>();
+        ensure!(
+            connected.len() == 2,
+            "The start tile on line {}, column {} connects to {} pipes, expected exactly 2",
+            row + 1,
+            col + 1,
+            connected.len()
+        );
+        // Two distinct directions always have a pipe connecting them
+        let start_pipe = pipe_connecting(connected[0], connected[1]).unwrap();
+        *grid.get_mut(row as isize, col as isize).unwrap() = start_pipe;
+
+        Ok(PipeMaze {
+            grid,
+            start: (row, col),
+        })
+    }
+}
+
+impl PipeMaze {
+    /// The pipe on the start tile, as inferred from its neighbours.
+    pub fn start_pipe(&self) -> char {
+        let (row, col) = self.start;
+        *self.grid.get(row as isize, col as isize).unwrap()
+    }
+
+    /// Walks the loop through the start tile, returning the position (row, column) of every
+    /// tile on it, in order and beginning with the start tile.
+    ///
+    /// Fails if the loop is broken, i.e. leads off the grid or into a tile that does not
+    /// connect back.
+    pub fn loop_tiles(&self) -> Result<Vec<(usize, usize)>> {
+        let mut tiles = vec![self.start];
+        let (mut row, mut col) = self.start;
+        let mut direction = connections(self.start_pipe()).unwrap()[0];
+
+        loop {
+            let (row_offset, col_offset) = direction.offset();
+            let (next_row, next_col) = (row as isize + row_offset, col as isize + col_offset);
+            let &tile = self.grid.get(next_row, next_col).with_context(|| {
+                format!(
+                    "The loop leads off the grid from line {}, column {}",
+                    row + 1,
+                    col + 1
+                )
+            })?;
+            (row, col) = (next_row as usize, next_col as usize);
+            if (row, col) == self.start {
+                return Ok(tiles);
+            }
+
+            let came_from = direction.opposite();
+            let Some([a, b]) = connections(tile).filter(|pipe| pipe.contains(&came_from)) else {
+                bail!(
+                    "The loop is broken on line {}, column {}: `{}` does not connect to the {:?}",
+                    row + 1,
+                    col + 1,
+                    tile,
+                    came_from
+                );
+            };
+            direction = if a == came_from { b } else { a };
+            tiles.push((row, col));
+        }
+    }
+
+    /// Counts the tiles that are enclosed by the loop, given the positions of all tiles on it.
+    ///
+    /// Scans every row from left to right, keeping track of whether we are inside of the loop:
+    /// Crossing a loop tile that connects to the north flips this (even-odd rule). For `L-J`,
+    /// both ends connect north, so the loop is just touched and the flips cancel out. For `L-7`,
+    /// only one end connects north, so the loop is crossed.
+    pub fn enclosed_tiles(&self, loop_tiles: &HashSet<(usize, usize)>) -> u64 {
+        let mut enclosed = 0;
+        for (row_idx, row) in self.grid.rows().enumerate() {
+            let mut inside = false;
+            for (col_idx, &tile) in row.iter().enumerate() {
+                if loop_tiles.contains(&(row_idx, col_idx)) {
+                    if connections(tile).is_some_and(|pipe| pipe.contains(&Direction::North)) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    enclosed += 1;
+                }
+            }
+        }
+        enclosed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SIMPLE_LOOP: &str = "\
+-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF";
+
+    const COMPLEX_LOOP: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+
+    const ENCLOSED_4: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+    const ENCLOSED_8: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+
+    const ENCLOSED_10: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
+
+    #[test]
+    fn test_part1_examples() {
+        assert_eq!(solve(SIMPLE_LOOP).unwrap().part1, 4);
+        assert_eq!(solve(COMPLEX_LOOP).unwrap().part1, 8);
+    }
+
+    #[test]
+    fn test_part2_examples() {
+        assert_eq!(solve(ENCLOSED_4).unwrap().part2, 4);
+        assert_eq!(solve(ENCLOSED_8).unwrap().part2, 8);
+        assert_eq!(solve(ENCLOSED_10).unwrap().part2, 10);
+    }
+
+    #[test]
+    fn test_start_pipe() {
+        assert_eq!(SIMPLE_LOOP.parse::<PipeMaze>().unwrap().start_pipe(), 'F');
+        assert_eq!(COMPLEX_LOOP.parse::<PipeMaze>().unwrap().start_pipe(), 'F');
+        assert_eq!(ENCLOSED_10.parse::<PipeMaze>().unwrap().start_pipe(), '7');
+    }
+
+    #[test]
+    fn test_loop_tiles_order() {
+        let maze = SIMPLE_LOOP.parse::<PipeMaze>().unwrap();
+        assert_eq!(
+            maze.loop_tiles().unwrap(),
+            vec![
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (3, 2),
+                (3, 3),
+                (2, 3),
+                (1, 3),
+                (1, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_start() {
+        let err = ".....\n.....".parse::<PipeMaze>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The maze does not contain a start tile `S`"
+        );
+
+        assert!("S-S".parse::<PipeMaze>().is_err());
+
+        let err = ".S-.".parse::<PipeMaze>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The start tile on line 1, column 2 connects to 1 pipes, expected exactly 2"
+        );
+    }
+
+    #[test]
+    fn test_broken_loop() {
+        let maze = "S-7\n|.|\nL-.".parse::<PipeMaze>().unwrap();
+        let err = maze.loop_tiles().unwrap_err();
+        assert!(err.to_string().starts_with("The loop is broken on line 3"));
+    }
+}
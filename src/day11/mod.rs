@@ -0,0 +1,212 @@
+use crate::answer::DayResult;
+use crate::grid::Grid;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, Result};
+use std::collections::HashSet;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+/// Every empty row and column becomes this many rows or columns in part 1.
+pub const PART1_EXPANSION: u64 = 2;
+/// Every empty row and column becomes this many rows or columns in part 2.
+pub const PART2_EXPANSION: u64 = 1_000_000;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (image of the universe, with `#` for galaxies
+    /// and `.` for empty space).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Instead of the two puzzle answers, print the sum of distances when every empty row and
+    /// column becomes this many rows or columns. Defaults to the puzzle values
+    /// (2 for part 1, 1000000 for part 2).
+    #[arg(long)]
+    pub expansion: Option<u64>,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of the distances between all pairs of galaxies, with an expansion factor of 2
+    pub part1: u64,
+    /// Sum of the distances between all pairs of galaxies, with an expansion factor of 1000000
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    if let Some(expansion) = args.expansion {
+        let sum = solve_repeatedly(&input, args.repeat, |input| {
+            Ok(sum_of_distances(&parse_galaxies(input)?, expansion))
+        })?;
+        info!(
+            "Sum of distances between all galaxies with expansion factor {}: {}",
+            expansion, sum
+        );
+        return Ok(());
+    }
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!(
+        "(Part 1) Sum of distances between all galaxies: {}",
+        answers.part1
+    );
+    info!(
+        "(Part 2) Sum of distances between all galaxies in the older universe: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day11::{solve, Answers};
+/// let answers = solve("#..\n...\n..#\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 6, part2: 2_000_002 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let galaxies = parse_galaxies(input)?;
+
+    Ok(Answers {
+        part1: sum_of_distances(&galaxies, PART1_EXPANSION),
+        part2: sum_of_distances(&galaxies, PART2_EXPANSION),
+    })
+}
+
+/// Returns the position (row, column) of every galaxy (`#`) in the image, row by row.
+/// All lines must be equally long and may only contain `#` and `.`.
+pub fn parse_galaxies(input: &str) -> Result<Vec<(usize, usize)>> {
+    let grid = input.parse::<Grid<char>>()?;
+
+    let mut galaxies = vec![];
+    for (row, col, &cell) in grid.iter() {
+        match cell {
+            '#' => galaxies.push((row, col)),
+            '.' => {}
+            _ => bail!(
+                "Invalid character `{}` on line {}, column {}, expected `#` or `.`",
+                cell,
+                row + 1,
+                col + 1
+            ),
+        }
+    }
+    Ok(galaxies)
+}
+
+/// Sums up the Manhattan distances between every pair of galaxies, after every row and column
+/// without a galaxy has been replaced by `expansion` empty rows or columns.
+pub fn sum_of_distances(galaxies: &[(usize, usize)], expansion: u64) -> u64 {
+    let rows = expand(galaxies.iter().map(|&(row, _)| row), expansion);
+    let cols = expand(galaxies.iter().map(|&(_, col)| col), expansion);
+
+    let mut sum = 0;
+    for a in 0..galaxies.len() {
+        for b in a + 1..galaxies.len() {
+            sum += rows[a].abs_diff(rows[b]) + cols[a].abs_diff(cols[b]);
+        }
+    }
+    sum
+}
+
+/// Maps every coordinate (of one axis) to where it ends up once the empty rows or columns
+/// before it have been expanded, keeping the order of `coords`.
+fn expand(coords: impl Iterator<Item = usize> + Clone, expansion: u64) -> Vec<i64> {
+    let occupied = coords.clone().collect::<HashSet<_>>();
+    let max = occupied.iter().copied().max().unwrap_or(0);
+
+    // empty_before[i] = number of empty rows or columns before i
+    let mut empty_before = Vec::with_capacity(max + 1);
+    let mut empty = 0;
+    for i in 0..=max {
+        empty_before.push(empty);
+        if !occupied.contains(&i) {
+            empty += 1;
+        }
+    }
+
+    // With an expansion of 0, empty rows or columns disappear, hence the signed arithmetic
+    let growth = expansion as i64 - 1;
+    coords
+        .map(|coord| coord as i64 + empty_before[coord] * growth)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn test_sum_of_distances_example_data() {
+        let galaxies = parse_galaxies(EXAMPLE_INPUT).unwrap();
+        assert_eq!(sum_of_distances(&galaxies, 2), 374);
+        assert_eq!(sum_of_distances(&galaxies, 10), 1030);
+        assert_eq!(sum_of_distances(&galaxies, 100), 8410);
+    }
+
+    #[test]
+    fn test_solve_example_data() {
+        let answers = solve(EXAMPLE_INPUT).unwrap();
+        assert_eq!(answers.part1, 374);
+        assert_eq!(answers.part2, 82000210);
+    }
+
+    #[test]
+    fn test_no_expansion() {
+        // Galaxies at (0, 0) and (2, 2), with one empty row and column between them
+        let galaxies = parse_galaxies("#..\n...\n..#").unwrap();
+        assert_eq!(sum_of_distances(&galaxies, 1), 4);
+        assert_eq!(sum_of_distances(&galaxies, 0), 2);
+    }
+
+    #[test]
+    fn test_parse_galaxies_invalid_character() {
+        let err = parse_galaxies("#.\n.x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid character `x` on line 2, column 2, expected `#` or `.`"
+        );
+    }
+}
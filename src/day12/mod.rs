@@ -0,0 +1,304 @@
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::debug;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+const OPERATIONAL: u8 = b'.';
+const DAMAGED: u8 = b'#';
+const UNKNOWN: u8 = b'?';
+
+/// How many times the records are repeated in part 2.
+pub const PART2_UNFOLD_TIMES: usize = 5;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (one condition record per line, like `???.### 1,1,3`).
+    /// Empty lines are ignored.
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of the possible arrangements of every record
+    pub part1: u64,
+    /// Sum of the possible arrangements of every record, after unfolding it
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Sum of possible arrangements: {}", answers.part1);
+    info!(
+        "(Part 2) Sum of possible arrangements after unfolding: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day12::{solve, Answers};
+/// let answers = solve("???.### 1,1,3\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 1, part2: 1 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let records = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_idx, line)| {
+            line.parse::<ConditionRecord>().with_context(|| {
+                format!("While trying to parse line {} (`{}`)", line_idx + 1, line)
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut part1 = 0;
+    let mut part2 = 0;
+    for record in &records {
+        let arrangements = record.count_arrangements();
+        let unfolded_arrangements = record.unfold(PART2_UNFOLD_TIMES).count_arrangements();
+        debug!(
+            "{:?}: {} arrangements, {} after unfolding",
+            record, arrangements, unfolded_arrangements
+        );
+
+        part1 += arrangements;
+        part2 += unfolded_arrangements;
+    }
+
+    Ok(Answers { part1, part2 })
+}
+
+/// One row of springs: the condition of every spring (`.` operational, `#` damaged,
+/// `?` unknown), and the sizes of the contiguous groups of damaged springs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionRecord {
+    pub pattern: Vec<u8>,
+    pub groups: Vec<usize>,
+}
+
+impl FromStr for ConditionRecord {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<ConditionRecord> {
+        let Some((pattern, groups)) = input.trim_end_matches('\r').split_once(' ') else {
+            bail!("Expected the springs and the group sizes, separated by a space");
+        };
+
+        if let Some(invalid) = pattern.chars().find(|c| !matches!(c, '.' | '#' | '?')) {
+            bail!(
+                "Invalid spring `{}`, expected one of `.`, `#` and `?`",
+                invalid
+            );
+        }
+
+        let groups = groups
+            .split(',')
+            .map(|group| {
+                group
+                    .parse::<usize>()
+                    .with_context(|| format!("Invalid group size `{}`", group))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ConditionRecord {
+            pattern: pattern.as_bytes().to_vec(),
+            groups,
+        })
+    }
+}
+
+impl ConditionRecord {
+    /// Repeats the record `times` times. The patterns are joined by an unknown spring `?`.
+    pub fn unfold(&self, times: usize) -> ConditionRecord {
+        let mut pattern = Vec::with_capacity((self.pattern.len() + 1) * times);
+        for i in 0..times {
+            if i > 0 {
+                pattern.push(UNKNOWN);
+            }
+            pattern.extend_from_slice(&self.pattern);
+        }
+
+        ConditionRecord {
+            pattern,
+            groups: self.groups.repeat(times),
+        }
+    }
+
+    /// See [`count_arrangements`].
+    pub fn count_arrangements(&self) -> u64 {
+        count_arrangements(&self.pattern, &self.groups)
+    }
+}
+
+/// Counts the ways in which the unknown springs (`?`) in `pattern` can be operational (`.`) or
+/// damaged (`#`), such that the damaged springs form exactly the given `groups`, in order.
+///
+/// ```
+/// # use advent_of_code_2023::day12::count_arrangements;
+/// assert_eq!(count_arrangements(b"?###????????", &[3, 2, 1]), 10);
+/// ```
+pub fn count_arrangements(pattern: &[u8], groups: &[usize]) -> u64 {
+    let mut memo = HashMap::new();
+    count_arrangements_from(pattern, groups, 0, 0, &mut memo)
+}
+
+/// Counts the arrangements of `pattern[pattern_idx..]` that form `groups[group_idx..]`.
+/// The result only depends on the two indices, so it is memoized on them.
+fn count_arrangements_from(
+    pattern: &[u8],
+    groups: &[usize],
+    pattern_idx: usize,
+    group_idx: usize,
+    memo: &mut HashMap<(usize, usize), u64>,
+) -> u64 {
+    if let Some(&count) = memo.get(&(pattern_idx, group_idx)) {
+        return count;
+    }
+
+    let rest = &pattern[pattern_idx.min(pattern.len())..];
+    let count = match groups.get(group_idx) {
+        // All groups are placed, so the remaining springs must all be operational
+        None => u64::from(!rest.contains(&DAMAGED)),
+        Some(&group) => {
+            let mut count = 0;
+
+            // The next spring is operational, the group starts later
+            if rest.first().is_some_and(|&spring| spring != DAMAGED) {
+                count += count_arrangements_from(pattern, groups, pattern_idx + 1, group_idx, memo);
+            }
+
+            // The group starts at the next spring. It needs room for `group` springs that can be
+            // damaged, followed by the end of the pattern or a spring that can be operational.
+            let fits = rest.len() >= group
+                && !rest[..group].contains(&OPERATIONAL)
+                && rest.get(group) != Some(&DAMAGED);
+            if fits {
+                // Skip the group and the operational spring after it
+                count += count_arrangements_from(
+                    pattern,
+                    groups,
+                    pattern_idx + group + 1,
+                    group_idx + 1,
+                    memo,
+                );
+            }
+
+            count
+        }
+    };
+
+    memo.insert((pattern_idx, group_idx), count);
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 21,
+                part2: 525152
+            }
+        );
+    }
+
+    #[test]
+    fn test_count_arrangements_per_line() {
+        let counts = EXAMPLE_INPUT
+            .lines()
+            .map(|line| line.parse::<ConditionRecord>().unwrap())
+            .map(|record| {
+                (
+                    record.count_arrangements(),
+                    record.unfold(PART2_UNFOLD_TIMES).count_arrangements(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![(1, 1), (4, 16384), (1, 1), (1, 16), (4, 2500), (10, 506250)]
+        );
+    }
+
+    #[test]
+    fn test_count_arrangements_edge_cases() {
+        assert_eq!(count_arrangements(b"", &[]), 1);
+        assert_eq!(count_arrangements(b"", &[1]), 0);
+        assert_eq!(count_arrangements(b"???", &[]), 1);
+        assert_eq!(count_arrangements(b"#", &[]), 0);
+        assert_eq!(count_arrangements(b"???", &[1]), 3);
+        assert_eq!(count_arrangements(b"##", &[1]), 0);
+    }
+
+    #[test]
+    fn test_unfold() {
+        let record = ".# 1".parse::<ConditionRecord>().unwrap();
+        assert_eq!(
+            record.unfold(5),
+            ConditionRecord {
+                pattern: b".#?.#?.#?.#?.#".to_vec(),
+                groups: vec![1, 1, 1, 1, 1],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("???.###".parse::<ConditionRecord>().is_err());
+        assert!("??x 1".parse::<ConditionRecord>().is_err());
+        assert!("??ä 1".parse::<ConditionRecord>().is_err());
+        assert!("??? 1,a".parse::<ConditionRecord>().is_err());
+    }
+}
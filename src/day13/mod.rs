@@ -0,0 +1,245 @@
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+use crate::parse::LineContext;
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, Context, Result};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use tracing::debug;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+/// Number of smudges on every mirror in part 2.
+pub const PART2_SMUDGES: usize = 1;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (patterns of ash `.` and rocks `#`, separated by empty lines).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of the reflection scores of every pattern
+    pub part1: u64,
+    /// Sum of the reflection scores of every pattern, after fixing the smudge on every mirror
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Sum of reflection scores: {}", answers.part1);
+    info!(
+        "(Part 2) Sum of reflection scores with smudges: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day13::{solve, Answers};
+/// let answers = solve("..\n..\n.#\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 100, part2: 1 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let patterns = parse_patterns(input)?;
+
+    let mut part1 = 0;
+    let mut part2 = 0;
+    for (pattern_idx, pattern) in patterns.iter().enumerate() {
+        let score = reflection_score(pattern, 0)
+            .with_context(|| format!("Pattern {} has no line of reflection", pattern_idx + 1))?;
+        let smudged_score = reflection_score(pattern, PART2_SMUDGES).with_context(|| {
+            format!(
+                "Pattern {} has no line of reflection with {} smudge(s)",
+                pattern_idx + 1,
+                PART2_SMUDGES
+            )
+        })?;
+        debug!(
+            "Pattern {}: score {}, {} with smudges",
+            pattern_idx + 1,
+            score,
+            smudged_score
+        );
+
+        part1 += score as u64;
+        part2 += smudged_score as u64;
+    }
+
+    Ok(Answers { part1, part2 })
+}
+
+/// Parses the patterns, which are separated by empty lines. Every pattern is a list of rows,
+/// where `true` is a rock (`#`) and `false` is ash (`.`).
+pub fn parse_patterns(input: &str) -> Result<Vec<Vec<Vec<bool>>>> {
+    let mut patterns = vec![];
+    let mut pattern: Vec<Vec<bool>> = vec![];
+
+    for (line_idx, line) in input.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            if !pattern.is_empty() {
+                patterns.push(std::mem::take(&mut pattern));
+            }
+            continue;
+        }
+
+        let row = line
+            .chars()
+            .map(|c| match c {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _ => bail!("Invalid character `{}`, expected `#` or `.`", c),
+            })
+            .collect::<Result<Vec<_>>>()
+            .and_then(|row| match pattern.first() {
+                Some(first) if first.len() != row.len() => bail!(
+                    "The row is {} characters long, but the pattern's first row is {}",
+                    row.len(),
+                    first.len()
+                ),
+                _ => Ok(row),
+            })
+            .with_context(|| LineContext::new(line_idx + 1, line))?;
+        pattern.push(row);
+    }
+    if !pattern.is_empty() {
+        patterns.push(pattern);
+    }
+
+    Ok(patterns)
+}
+
+/// Finds the line of reflection of the pattern, where exactly `smudges` cells differ from their
+/// mirror image (0 for a perfect mirror). Vertical lines are tried first.
+///
+/// Returns the number of columns left of a vertical line, or 100 times the number of rows above
+/// a horizontal line. `None` if there is no such line.
+///
+/// ```
+/// # use advent_of_code_2023::day13::reflection_score;
+/// let pattern = vec![vec![true, true, false], vec![false, false, true]];
+/// assert_eq!(reflection_score(&pattern, 0), Some(1));
+/// assert_eq!(reflection_score(&pattern, 1), None);
+/// ```
+pub fn reflection_score(pattern: &[Vec<bool>], smudges: usize) -> Option<usize> {
+    reflection_line(&transpose(pattern), smudges)
+        .or_else(|| reflection_line(pattern, smudges).map(|rows_above| 100 * rows_above))
+}
+
+/// Finds a horizontal line of reflection with exactly `smudges` mismatches,
+/// returning the number of rows above it.
+fn reflection_line(rows: &[Vec<bool>], smudges: usize) -> Option<usize> {
+    (1..rows.len()).find(|&rows_above| {
+        let above = rows[..rows_above].iter().rev();
+        let below = rows[rows_above..].iter();
+        let mismatches = above
+            .zip(below)
+            .map(|(a, b)| a.iter().zip(b).filter(|(a, b)| a != b).count())
+            .sum::<usize>();
+        mismatches == smudges
+    })
+}
+
+/// Turns the columns of the pattern into rows.
+fn transpose(pattern: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let width = pattern.first().map_or(0, Vec::len);
+    (0..width)
+        .map(|col| pattern.iter().map(|row| row[col]).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 405,
+                part2: 400
+            }
+        );
+    }
+
+    #[test]
+    fn test_reflection_score_per_pattern() {
+        let patterns = parse_patterns(EXAMPLE_INPUT).unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(reflection_score(&patterns[0], 0), Some(5));
+        assert_eq!(reflection_score(&patterns[1], 0), Some(400));
+        assert_eq!(reflection_score(&patterns[0], 1), Some(300));
+        assert_eq!(reflection_score(&patterns[1], 1), Some(100));
+        assert_eq!(reflection_score(&patterns[0], 100), None);
+    }
+
+    #[test]
+    fn test_parse_patterns_invalid() {
+        let err = parse_patterns("#.\n#x").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 2: `#x`");
+
+        let err = parse_patterns("#.\n#..\n\n#").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 2: `#..`");
+
+        // Rows of different patterns may have different lengths
+        assert_eq!(parse_patterns("#.\n\n#..\n").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_no_reflection() {
+        let err = solve("#.\n..\n").unwrap_err();
+        assert_eq!(err.to_string(), "Pattern 1 has no line of reflection");
+    }
+}
@@ -0,0 +1,309 @@
+use crate::answer::DayResult;
+use crate::grid::Grid;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use tracing::debug;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+const ROUNDED_ROCK: char = 'O';
+const CUBE_ROCK: char = '#';
+const EMPTY: char = '.';
+
+/// How many spin cycles are run in part 2.
+pub const PART2_SPIN_CYCLES: usize = 1_000_000_000;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (the platform, with rounded rocks `O`, cube-shaped rocks `#`
+    /// and empty space `.`).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Total load on the north support beams after tilting the platform north
+    pub part1: u64,
+    /// Total load on the north support beams after one billion spin cycles
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Total load after tilting north: {}", answers.part1);
+    info!(
+        "(Part 2) Total load after {} spin cycles: {}",
+        PART2_SPIN_CYCLES, answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day14::{solve, Answers};
+/// let answers = solve(".O\nO#\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 4, part2: 3 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let platform = parse_platform(input)?;
+
+    let mut tilted = platform.clone();
+    tilt(&mut tilted, Direction::North);
+
+    let mut spun = platform;
+    spin_cycles(&mut spun, PART2_SPIN_CYCLES);
+
+    Ok(Answers {
+        part1: total_load(&tilted) as u64,
+        part2: total_load(&spun) as u64,
+    })
+}
+
+/// Parses the platform. All lines must be equally long and may only contain
+/// `O`, `#` and `.`.
+pub fn parse_platform(input: &str) -> Result<Grid<char>> {
+    let grid = input.parse::<Grid<char>>()?;
+
+    if let Some((row, col, cell)) = grid
+        .iter()
+        .find(|&(_, _, &cell)| !matches!(cell, ROUNDED_ROCK | CUBE_ROCK | EMPTY))
+    {
+        bail!(
+            "Invalid character `{}` on line {}, column {}, expected `O`, `#` or `.`",
+            cell,
+            row + 1,
+            col + 1
+        );
+    }
+    Ok(grid)
+}
+
+/// One of the four directions the platform can be tilted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+/// Tilts the platform, so that every rounded rock rolls into the given direction until it hits
+/// the edge, a cube-shaped rock or another rounded rock.
+pub fn tilt(grid: &mut Grid<char>, direction: Direction) {
+    // Every line (column for north and south, row for west and east) is tilted separately,
+    // `pos` counts from the edge the rocks roll towards
+    let (lines, len) = match direction {
+        Direction::North | Direction::South => (grid.width(), grid.height()),
+        Direction::West | Direction::East => (grid.height(), grid.width()),
+    };
+    let position = |line: usize, pos: usize| -> (isize, isize) {
+        let (row, col) = match direction {
+            Direction::North => (pos, line),
+            Direction::South => (len - 1 - pos, line),
+            Direction::West => (line, pos),
+            Direction::East => (line, len - 1 - pos),
+        };
+        (row as isize, col as isize)
+    };
+
+    for line in 0..lines {
+        // Where the next rounded rock comes to a halt
+        let mut free = 0;
+        for pos in 0..len {
+            let (row, col) = position(line, pos);
+            match grid.get(row, col) {
+                Some(&CUBE_ROCK) => free = pos + 1,
+                Some(&ROUNDED_ROCK) => {
+                    let (free_row, free_col) = position(line, free);
+                    *grid.get_mut(row, col).unwrap() = EMPTY;
+                    *grid.get_mut(free_row, free_col).unwrap() = ROUNDED_ROCK;
+                    free += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Tilts the platform north, west, south and east, in that order.
+pub fn spin_cycle(grid: &mut Grid<char>) {
+    for direction in [
+        Direction::North,
+        Direction::West,
+        Direction::South,
+        Direction::East,
+    ] {
+        tilt(grid, direction);
+    }
+}
+
+/// Runs [`spin_cycle`] `cycles` times. The platform eventually repeats itself, so once a state is
+/// seen for the second time, the remaining cycles are skipped over.
+pub fn spin_cycles(grid: &mut Grid<char>, cycles: usize) {
+    // Cycle count at which every state was first seen
+    let mut seen = HashMap::<String, usize>::new();
+
+    let mut cycle = 0;
+    while cycle < cycles {
+        if let Some(first_seen) = seen.insert(state_key(grid), cycle) {
+            let period = cycle - first_seen;
+            debug!(
+                "State after {} cycles repeats after {} cycles",
+                first_seen, period
+            );
+            for _ in 0..(cycles - cycle) % period {
+                spin_cycle(grid);
+            }
+            return;
+        }
+
+        spin_cycle(grid);
+        cycle += 1;
+    }
+}
+
+/// The platform as a string, to remember which states have already been seen.
+fn state_key(grid: &Grid<char>) -> String {
+    grid.rows().flatten().collect()
+}
+
+/// Every rounded rock puts a load onto the north support beams equal to the number of rows from
+/// it to the south edge of the platform, including its own row.
+pub fn total_load(grid: &Grid<char>) -> usize {
+    grid.iter()
+        .filter(|&(_, _, &cell)| cell == ROUNDED_ROCK)
+        .map(|(row, _, _)| grid.height() - row)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 136,
+                part2: 64
+            }
+        );
+    }
+
+    #[test]
+    fn test_tilt_north() {
+        let mut grid = parse_platform(EXAMPLE_INPUT).unwrap();
+        tilt(&mut grid, Direction::North);
+        assert_eq!(
+            grid,
+            parse_platform(
+                "\
+OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#...."
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_spin_cycle() {
+        let mut grid = parse_platform(EXAMPLE_INPUT).unwrap();
+        spin_cycle(&mut grid);
+        assert_eq!(
+            grid,
+            parse_platform(
+                "\
+.....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#...."
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_spin_cycles_matches_naive() {
+        for cycles in [0, 1, 2, 3, 10, 25] {
+            let mut fast = parse_platform(EXAMPLE_INPUT).unwrap();
+            spin_cycles(&mut fast, cycles);
+
+            let mut naive = parse_platform(EXAMPLE_INPUT).unwrap();
+            for _ in 0..cycles {
+                spin_cycle(&mut naive);
+            }
+            assert_eq!(fast, naive, "after {} cycles", cycles);
+        }
+    }
+
+    #[test]
+    fn test_parse_platform_invalid_character() {
+        let err = parse_platform("O.\n.x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid character `x` on line 2, column 2, expected `O`, `#` or `.`"
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(feature = "cli")]
+use tracing::info;
+use tracing::trace;
+
+/// Number of boxes the lenses are arranged in, one for every possible [`hash`].
+pub const NUM_BOXES: usize = 256;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (the initialization sequence, comma-separated steps like
+    /// `rn=1,cm-`). Line breaks are ignored.
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of the HASH of every step
+    pub part1: u64,
+    /// Focusing power of the lenses after running all steps
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Sum of the HASH of every step: {}", answers.part1);
+    info!("(Part 2) Focusing power: {}", answers.part2);
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day15::{solve, Answers};
+/// let answers = solve("rn=1,cm-,qp=3\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 30 + 253 + 97, part2: 1 + 2 * 3 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let steps = split_steps(input);
+
+    let part1 = steps.iter().map(|step| hash(step) as u64).sum();
+
+    let steps = steps
+        .iter()
+        .enumerate()
+        .map(|(step_idx, step)| {
+            step.parse::<Step>().with_context(|| {
+                format!("While trying to parse step {} (`{}`)", step_idx + 1, step)
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut boxes = Boxes::new();
+    for step in &steps {
+        boxes.apply(step);
+        trace!("After {:?}: {:?}", step, boxes);
+    }
+
+    Ok(Answers {
+        part1,
+        part2: boxes.focusing_power(),
+    })
+}
+
+/// Splits the initialization sequence into its steps. Line breaks are ignored,
+/// as are empty steps (e.g. after a trailing comma).
+pub fn split_steps(input: &str) -> Vec<String> {
+    input
+        .replace(['\r', '\n'], "")
+        .split(',')
+        .filter(|step| !step.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// The Holiday ASCII String Helper algorithm: Starting at 0, for every character, adds its ASCII
+/// code, multiplies by 17 and takes the remainder of dividing by 256.
+///
+/// ```
+/// # use advent_of_code_2023::day15::hash;
+/// assert_eq!(hash("HASH"), 52);
+/// ```
+pub fn hash(s: &str) -> u8 {
+    // Wrapping u8 arithmetic is arithmetic modulo 256
+    s.bytes()
+        .fold(0u8, |acc, byte| acc.wrapping_add(byte).wrapping_mul(17))
+}
+
+/// One step of the initialization sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Label of the lens, whose [`hash`] is the box the step applies to
+    pub label: String,
+    pub operation: Operation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// `-`: Removes the lens with the label from its box, if there is one
+    Remove,
+    /// `=N`: Replaces the lens with the label in its box by one with focal length `N`,
+    /// or adds it to the back of the box if there is none
+    Insert(u8),
+}
+
+impl FromStr for Step {
+    type Err = anyhow::Error;
+
+    fn from_str(step: &str) -> Result<Step> {
+        let (label, operation) = if let Some(label) = step.strip_suffix('-') {
+            (label, Operation::Remove)
+        } else if let Some((label, focal_length)) = step.split_once('=') {
+            let focal_length = focal_length
+                .parse::<u8>()
+                .with_context(|| format!("Invalid focal length `{}`", focal_length))?;
+            (label, Operation::Insert(focal_length))
+        } else {
+            bail!("Expected the step to end with `-` or `=` and a focal length");
+        };
+
+        ensure!(
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphabetic()),
+            "Invalid label `{}`, expected ASCII letters",
+            label
+        );
+
+        Ok(Step {
+            label: label.to_owned(),
+            operation,
+        })
+    }
+}
+
+/// A lens with its label, as stored in one of the [`Boxes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lens {
+    pub label: String,
+    pub focal_length: u8,
+}
+
+/// The [`NUM_BOXES`] boxes, each with its lenses in order from front to back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boxes(pub Vec<Vec<Lens>>);
+
+impl Default for Boxes {
+    fn default() -> Boxes {
+        Boxes::new()
+    }
+}
+
+impl Boxes {
+    /// All boxes, without any lenses.
+    pub fn new() -> Boxes {
+        Boxes(vec![vec![]; NUM_BOXES])
+    }
+
+    /// Runs the step on the box given by the [`hash`] of its label.
+    pub fn apply(&mut self, step: &Step) {
+        let lenses = &mut self.0[hash(&step.label) as usize];
+        let position = lenses.iter().position(|lens| lens.label == step.label);
+
+        match (step.operation, position) {
+            (Operation::Remove, Some(position)) => {
+                lenses.remove(position);
+            }
+            (Operation::Remove, None) => {}
+            (Operation::Insert(focal_length), Some(position)) => {
+                lenses[position].focal_length = focal_length;
+            }
+            (Operation::Insert(focal_length), None) => lenses.push(Lens {
+                label: step.label.clone(),
+                focal_length,
+            }),
+        }
+    }
+
+    /// Sums up `(box number) * (slot number) * (focal length)` of every lens, where box and slot
+    /// numbers are 1-based.
+    pub fn focusing_power(&self) -> u64 {
+        self.0
+            .iter()
+            .zip(1..)
+            .flat_map(|(lenses, box_number)| {
+                lenses
+                    .iter()
+                    .zip(1..)
+                    .map(move |(lens, slot)| box_number * slot * lens.focal_length as u64)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 1320,
+                part2: 145
+            }
+        );
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(""), 0);
+        assert_eq!(hash("HASH"), 52);
+        let hashes = split_steps(EXAMPLE_INPUT)
+            .iter()
+            .map(|step| hash(step))
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, vec![30, 253, 97, 47, 14, 180, 9, 197, 48, 214, 231]);
+    }
+
+    #[test]
+    fn test_split_steps_ignores_line_breaks() {
+        assert_eq!(
+            split_steps("rn=1,c\r\nm-,\n"),
+            vec!["rn=1".to_owned(), "cm-".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_boxes_example_data() {
+        let mut boxes = Boxes::new();
+        for step in split_steps(EXAMPLE_INPUT) {
+            boxes.apply(&step.parse().unwrap());
+        }
+
+        let lens = |label: &str, focal_length| Lens {
+            label: label.to_owned(),
+            focal_length,
+        };
+        assert_eq!(boxes.0[0], vec![lens("rn", 1), lens("cm", 2)]);
+        assert_eq!(
+            boxes.0[3],
+            vec![lens("ot", 7), lens("ab", 5), lens("pc", 6)]
+        );
+        assert_eq!(
+            boxes.0.iter().filter(|lenses| !lenses.is_empty()).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_remove_absent_label_is_noop() {
+        let mut boxes = Boxes::new();
+        boxes.apply(&"rn=1".parse().unwrap());
+        let before = boxes.clone();
+
+        // `cm` is in the same box as `rn`, `qp` is not
+        boxes.apply(&"cm-".parse().unwrap());
+        boxes.apply(&"qp-".parse().unwrap());
+        assert_eq!(boxes, before);
+        assert_eq!(boxes.focusing_power(), 1);
+    }
+
+    #[test]
+    fn test_parse_step_invalid() {
+        assert!("rn".parse::<Step>().is_err());
+        assert!("=1".parse::<Step>().is_err());
+        assert!("rn=x".parse::<Step>().is_err());
+        assert!("r1-".parse::<Step>().is_err());
+
+        let err = solve("rn=1,cm").unwrap_err();
+        assert_eq!(err.to_string(), "While trying to parse step 2 (`cm`)");
+    }
+}
@@ -0,0 +1,291 @@
+use crate::answer::DayResult;
+use crate::grid::Grid;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, Result};
+use std::collections::HashSet;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (the contraption, with empty space `.`, mirrors `/` and `\`
+    /// and splitters `|` and `-`).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Energized tiles when the beam enters at the top-left corner, heading east
+    pub part1: u64,
+    /// Most energized tiles for any beam entering from an edge
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Energized tiles: {}", answers.part1);
+    info!(
+        "(Part 2) Most energized tiles from any edge: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day16::{solve, Answers};
+/// let answers = solve(".\\\n..\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 3, part2: 3 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let grid = parse_contraption(input)?;
+
+    let part1 = energized(
+        &grid,
+        Beam {
+            row: 0,
+            col: 0,
+            direction: Direction::East,
+        },
+    );
+    let part2 = edge_beams(&grid)
+        .map(|start| energized(&grid, start))
+        .max()
+        .unwrap_or(0);
+
+    Ok(Answers {
+        part1: part1 as u64,
+        part2: part2 as u64,
+    })
+}
+
+/// Parses the contraption. All lines must be equally long and may only contain
+/// `.`, `/`, `\`, `|` and `-`.
+pub fn parse_contraption(input: &str) -> Result<Grid<char>> {
+    let grid = input.parse::<Grid<char>>()?;
+
+    if let Some((row, col, tile)) = grid
+        .iter()
+        .find(|&(_, _, &tile)| !matches!(tile, '.' | '/' | '\\' | '|' | '-'))
+    {
+        bail!(
+            "Invalid character `{}` on line {}, column {}, expected one of `.`, `/`, `\\`, `|` and `-`",
+            tile,
+            row + 1,
+            col + 1
+        );
+    }
+    Ok(grid)
+}
+
+/// The direction a beam of light travels into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Offset (row, column) of one step into this direction.
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+}
+
+/// A beam of light on a tile, travelling into a direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Beam {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+/// The directions a beam travels into after passing the given tile.
+/// Mirrors reflect it, splitters it hits on the flat side split it into two beams.
+fn outgoing(tile: char, direction: Direction) -> Vec<Direction> {
+    use Direction::*;
+    match (tile, direction) {
+        ('/', North) => vec![East],
+        ('/', East) => vec![North],
+        ('/', South) => vec![West],
+        ('/', West) => vec![South],
+        ('\\', North) => vec![West],
+        ('\\', East) => vec![South],
+        ('\\', South) => vec![East],
+        ('\\', West) => vec![North],
+        ('|', East | West) => vec![North, South],
+        ('-', North | South) => vec![East, West],
+        _ => vec![direction],
+    }
+}
+
+/// Follows the beam that enters the grid at `start` (including all beams it is split into),
+/// and counts the tiles that at least one beam passes.
+///
+/// Beams can run in circles, so every state (tile and direction) is only followed once.
+pub fn energized(grid: &Grid<char>, start: Beam) -> usize {
+    let mut visited = HashSet::new();
+    let mut beams = vec![start];
+
+    while let Some(beam) = beams.pop() {
+        let Some(&tile) = grid.get(beam.row as isize, beam.col as isize) else {
+            continue;
+        };
+        if !visited.insert(beam) {
+            continue;
+        }
+
+        for direction in outgoing(tile, beam.direction) {
+            let (row_offset, col_offset) = direction.offset();
+            // Beams that leave the grid are dropped by the check above, once they
+            // are popped. Leaving the grid on the top or left is an underflow, though.
+            let (Some(row), Some(col)) = (
+                beam.row.checked_add_signed(row_offset),
+                beam.col.checked_add_signed(col_offset),
+            ) else {
+                continue;
+            };
+            beams.push(Beam {
+                row,
+                col,
+                direction,
+            });
+        }
+    }
+
+    visited
+        .into_iter()
+        .map(|beam| (beam.row, beam.col))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Every beam that enters the grid from one of its edges, heading away from that edge.
+pub fn edge_beams(grid: &Grid<char>) -> impl Iterator<Item = Beam> + '_ {
+    let (height, width) = (grid.height(), grid.width());
+    let beam = |row, col, direction| Beam {
+        row,
+        col,
+        direction,
+    };
+
+    let from_sides = (0..height).flat_map(move |row| {
+        [
+            beam(row, 0, Direction::East),
+            beam(row, width.saturating_sub(1), Direction::West),
+        ]
+    });
+    let from_top_and_bottom = (0..width).flat_map(move |col| {
+        [
+            beam(0, col, Direction::South),
+            beam(height.saturating_sub(1), col, Direction::North),
+        ]
+    });
+    from_sides.chain(from_top_and_bottom)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 46,
+                part2: 51
+            }
+        );
+    }
+
+    #[test]
+    fn test_energized_best_start() {
+        let grid = parse_contraption(EXAMPLE_INPUT).unwrap();
+        let start = Beam {
+            row: 0,
+            col: 3,
+            direction: Direction::South,
+        };
+        assert_eq!(energized(&grid, start), 51);
+    }
+
+    #[test]
+    fn test_energized_loop_terminates() {
+        // The beam enters a loop of four mirrors and keeps circling
+        let grid = parse_contraption("/.\\\n...\n\\./\n").unwrap();
+        let start = Beam {
+            row: 0,
+            col: 1,
+            direction: Direction::East,
+        };
+        assert_eq!(energized(&grid, start), 8);
+    }
+
+    #[test]
+    fn test_edge_beams() {
+        let grid = parse_contraption("...\n...\n").unwrap();
+        assert_eq!(edge_beams(&grid).count(), 2 * 2 + 2 * 3);
+    }
+
+    #[test]
+    fn test_parse_contraption_invalid_character() {
+        let err = parse_contraption(".|\n.x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid character `x` on line 2, column 2, expected one of `.`, `/`, `\\`, `|` and `-`"
+        );
+    }
+}
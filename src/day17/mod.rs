@@ -0,0 +1,296 @@
+use crate::answer::DayResult;
+use crate::grid::Grid;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, Context, Result};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+/// Minimum and maximum number of blocks a crucible moves in a straight line (part 1).
+pub const CRUCIBLE_RUN: (usize, usize) = (1, 3);
+/// Minimum and maximum number of blocks an ultra crucible moves in a straight line (part 2).
+pub const ULTRA_CRUCIBLE_RUN: (usize, usize) = (4, 10);
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (the map of heat loss per city block, one digit per block).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Least heat loss from the top-left to the bottom-right block with a crucible
+    pub part1: u64,
+    /// Least heat loss from the top-left to the bottom-right block with an ultra crucible
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!(
+        "(Part 1) Least heat loss with a crucible: {}",
+        answers.part1
+    );
+    info!(
+        "(Part 2) Least heat loss with an ultra crucible: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day17::{solve, Answers};
+/// let answers = solve("11111\n11111\n11111\n11111\n11111\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 8, part2: 8 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let grid = parse_heat_loss_map(input)?;
+
+    let (min_run, max_run) = CRUCIBLE_RUN;
+    let part1 = min_heat_loss(&grid, min_run, max_run)
+        .context("The crucible can't reach the bottom-right block")?;
+    let (min_run, max_run) = ULTRA_CRUCIBLE_RUN;
+    let part2 = min_heat_loss(&grid, min_run, max_run)
+        .context("The ultra crucible can't reach the bottom-right block")?;
+
+    Ok(Answers { part1, part2 })
+}
+
+/// Parses the map of heat loss per city block. All lines must be equally long and may only
+/// contain digits.
+pub fn parse_heat_loss_map(input: &str) -> Result<Grid<u8>> {
+    let grid = input.parse::<Grid<char>>()?;
+
+    let rows = grid
+        .rows()
+        .enumerate()
+        .map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .map(|(col, &cell)| match cell.to_digit(10) {
+                    Some(heat_loss) => Ok(heat_loss as u8),
+                    None => bail!(
+                        "Invalid character `{}` on line {}, column {}, expected a digit",
+                        cell,
+                        row + 1,
+                        col + 1
+                    ),
+                })
+                .collect()
+        })
+        .collect::<Result<Vec<Vec<u8>>>>()?;
+    Ok(Grid::from_rows(rows, 0))
+}
+
+/// The direction the crucible is moving into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Offset (row, column) of one step into this direction.
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+
+    /// The directions the crucible can turn into, 90 degrees to the left or right.
+    fn turns(self) -> [Direction; 2] {
+        match self {
+            Direction::North | Direction::South => [Direction::East, Direction::West],
+            Direction::East | Direction::West => [Direction::North, Direction::South],
+        }
+    }
+}
+
+/// Where the crucible is, and how it got there: the direction of its last move and how many
+/// blocks it has moved into that direction in a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct State {
+    row: usize,
+    col: usize,
+    direction: Direction,
+    consecutive_steps: usize,
+}
+
+/// Finds the least heat loss on the way from the top-left to the bottom-right block, using
+/// Dijkstra's algorithm. The heat loss of the starting block doesn't count.
+///
+/// The crucible has to move at least `min_run` and at most `max_run` blocks in a straight line
+/// before turning (or stopping at the end), and can never reverse.
+/// `None` if the bottom-right block can't be reached under these rules.
+pub fn min_heat_loss(grid: &Grid<u8>, min_run: usize, max_run: usize) -> Option<u64> {
+    let target = (grid.height().checked_sub(1)?, grid.width().checked_sub(1)?);
+
+    let mut best = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    // The crucible hasn't moved yet, so it may start off into either direction
+    for direction in [Direction::East, Direction::South] {
+        let start = State {
+            row: 0,
+            col: 0,
+            direction,
+            consecutive_steps: 0,
+        };
+        best.insert(start, 0);
+        queue.push(Reverse((0, start)));
+    }
+
+    while let Some(Reverse((heat_loss, state))) = queue.pop() {
+        if (state.row, state.col) == target && state.consecutive_steps >= min_run {
+            return Some(heat_loss);
+        }
+        if best.get(&state).is_some_and(|&best| best < heat_loss) {
+            continue;
+        }
+
+        let can_turn = state.consecutive_steps >= min_run || state.consecutive_steps == 0;
+        let straight = (state.consecutive_steps < max_run).then_some(state.direction);
+        let turns = state.direction.turns().into_iter().filter(|_| can_turn);
+
+        for direction in straight.into_iter().chain(turns) {
+            let (row_offset, col_offset) = direction.offset();
+            let (Some(row), Some(col)) = (
+                state.row.checked_add_signed(row_offset),
+                state.col.checked_add_signed(col_offset),
+            ) else {
+                continue;
+            };
+            let Some(&block_heat_loss) = grid.get(row as isize, col as isize) else {
+                continue;
+            };
+
+            let next = State {
+                row,
+                col,
+                direction,
+                consecutive_steps: if direction == state.direction {
+                    state.consecutive_steps + 1
+                } else {
+                    1
+                },
+            };
+            let next_heat_loss = heat_loss + block_heat_loss as u64;
+            if best.get(&next).is_none_or(|&best| next_heat_loss < best) {
+                best.insert(next, next_heat_loss);
+                queue.push(Reverse((next_heat_loss, next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533
+";
+
+    const SECOND_EXAMPLE_INPUT: &str = "\
+111111111111
+999999999991
+999999999991
+999999999991
+999999999991
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 102,
+                part2: 94
+            }
+        );
+    }
+
+    #[test]
+    fn test_ultra_crucible_second_example() {
+        let grid = parse_heat_loss_map(SECOND_EXAMPLE_INPUT).unwrap();
+        let (min_run, max_run) = ULTRA_CRUCIBLE_RUN;
+        assert_eq!(min_heat_loss(&grid, min_run, max_run), Some(71));
+    }
+
+    #[test]
+    fn test_min_heat_loss_unreachable() {
+        // Too short for the ultra crucible to move four blocks in a row
+        let grid = parse_heat_loss_map("123\n").unwrap();
+        assert_eq!(min_heat_loss(&grid, 1, 3), Some(5));
+        assert_eq!(min_heat_loss(&grid, 4, 10), None);
+
+        let err = solve("123\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The ultra crucible can't reach the bottom-right block"
+        );
+    }
+
+    #[test]
+    fn test_parse_heat_loss_map_invalid_character() {
+        let err = parse_heat_loss_map("12\n3x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid character `x` on line 2, column 2, expected a digit"
+        );
+    }
+}
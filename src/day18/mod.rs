@@ -0,0 +1,303 @@
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+use crate::parse::parse_lines;
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (the dig plan, one instruction like `R 6 (#70c710)` per line).
+    /// Empty lines are ignored.
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Cubic meters of lava the lagoon holds, following the directions and distances
+    pub part1: u64,
+    /// Cubic meters of lava the lagoon holds, following the instructions hidden in the colors
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Lagoon volume: {}", answers.part1);
+    info!(
+        "(Part 2) Lagoon volume with the color instructions: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day18::{solve, Answers};
+/// let answers = solve("R 2 (#000020)\nD 2 (#000021)\nL 2 (#000022)\nU 2 (#000023)\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 9, part2: 9 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let instructions = parse_dig_plan(input)?;
+
+    let moves = instructions
+        .iter()
+        .map(|instruction| (instruction.dir, instruction.distance))
+        .collect::<Vec<_>>();
+    let color_moves = instructions
+        .iter()
+        .map(|instruction| instruction.color_move())
+        .collect::<Vec<_>>();
+
+    Ok(Answers {
+        part1: lagoon_area(&moves) as u64,
+        part2: lagoon_area(&color_moves) as u64,
+    })
+}
+
+/// The direction the digger moves into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    /// Offset (x, y) of one meter into this direction, where y grows downwards.
+    fn offset(self) -> (i64, i64) {
+        match self {
+            Dir::Up => (0, -1),
+            Dir::Down => (0, 1),
+            Dir::Left => (-1, 0),
+            Dir::Right => (1, 0),
+        }
+    }
+}
+
+/// One line of the dig plan, like `R 6 (#70c710)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub dir: Dir,
+    /// Meters to dig into `dir`
+    pub distance: i64,
+    /// The hexadecimal color code, like `0x70c710`
+    pub color: u32,
+}
+
+impl FromStr for Instruction {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> Result<Instruction> {
+        let mut parts = line.split_whitespace();
+        let (Some(dir), Some(distance), Some(color), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            bail!("Expected a direction, a distance and a color, like `R 6 (#70c710)`");
+        };
+
+        let dir = match dir {
+            "U" => Dir::Up,
+            "D" => Dir::Down,
+            "L" => Dir::Left,
+            "R" => Dir::Right,
+            _ => bail!("Invalid direction `{}`, expected `U`, `D`, `L` or `R`", dir),
+        };
+        let distance = distance
+            .parse::<i64>()
+            .with_context(|| format!("Invalid distance `{}`", distance))?;
+        ensure!(distance > 0, "The distance must be positive");
+        let hex = color
+            .strip_prefix("(#")
+            .and_then(|color| color.strip_suffix(')'))
+            .filter(|hex| hex.len() == 6)
+            .with_context(|| format!("Invalid color `{}`, expected e.g. `(#70c710)`", color))?;
+        let color = u32::from_str_radix(hex, 16)
+            .with_context(|| format!("Invalid color `{}`, expected e.g. `(#70c710)`", color))?;
+        ensure!(
+            color & 0xf <= 3,
+            "The last digit of the color must be a direction from 0 to 3"
+        );
+
+        Ok(Instruction {
+            dir,
+            distance,
+            color,
+        })
+    }
+}
+
+impl Instruction {
+    /// The move hidden in the color (part 2): The first five hex digits are the distance,
+    /// the last one is the direction (`0` right, `1` down, `2` left, `3` up).
+    ///
+    /// Panics if the last digit is not a direction, which parsing already rules out.
+    ///
+    /// ```
+    /// # use advent_of_code_2023::day18::{Dir, Instruction};
+    /// let instruction = "R 6 (#70c710)".parse::<Instruction>().unwrap();
+    /// assert_eq!(instruction.color_move(), (Dir::Right, 461937));
+    /// ```
+    pub fn color_move(&self) -> (Dir, i64) {
+        let dir = match self.color & 0xf {
+            0 => Dir::Right,
+            1 => Dir::Down,
+            2 => Dir::Left,
+            3 => Dir::Up,
+            digit => panic!("Invalid direction {} in the color", digit),
+        };
+        (dir, (self.color >> 4) as i64)
+    }
+}
+
+/// Parses the dig plan, one [`Instruction`] per line. Empty lines are ignored.
+pub fn parse_dig_plan(input: &str) -> Result<Vec<Instruction>> {
+    parse_lines(input)
+}
+
+/// Computes how many cubic meters (tiles) the lagoon dug by following the moves holds, including
+/// the one meter wide trench around it. The moves have to form a closed loop.
+///
+/// The shoelace formula gives the area of the polygon through the centers of the trench tiles,
+/// and by Pick's theorem, the tiles strictly inside of it are `area - boundary / 2 + 1`.
+/// Adding the `boundary` tiles of the trench itself gives the result. This never looks at
+/// individual tiles, so the huge distances of part 2 are no problem.
+///
+/// ```
+/// # use advent_of_code_2023::day18::{lagoon_area, Dir};
+/// let square = [(Dir::Right, 2), (Dir::Down, 2), (Dir::Left, 2), (Dir::Up, 2)];
+/// assert_eq!(lagoon_area(&square), 9);
+/// ```
+pub fn lagoon_area(moves: &[(Dir, i64)]) -> i64 {
+    let (mut x, mut y) = (0i64, 0i64);
+    let mut twice_area = 0;
+    let mut boundary = 0;
+
+    for &(dir, distance) in moves {
+        let (dx, dy) = dir.offset();
+        let (next_x, next_y) = (x + dx * distance, y + dy * distance);
+        twice_area += x * next_y - next_x * y;
+        boundary += distance;
+        (x, y) = (next_x, next_y);
+    }
+
+    let area = twice_area.abs() / 2;
+    let interior = area - boundary / 2 + 1;
+    interior + boundary
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 62,
+                part2: 952408144115
+            }
+        );
+    }
+
+    #[test]
+    fn test_color_moves_example_data() {
+        let moves = parse_dig_plan(EXAMPLE_INPUT)
+            .unwrap()
+            .iter()
+            .map(Instruction::color_move)
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            moves,
+            vec![
+                (Dir::Right, 461937),
+                (Dir::Down, 56407),
+                (Dir::Right, 356671)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lagoon_area_direction_independent() {
+        // The same loop, once clockwise and once counter-clockwise
+        let clockwise = [
+            (Dir::Right, 3),
+            (Dir::Down, 1),
+            (Dir::Left, 3),
+            (Dir::Up, 1),
+        ];
+        let counter_clockwise = [
+            (Dir::Down, 1),
+            (Dir::Right, 3),
+            (Dir::Up, 1),
+            (Dir::Left, 3),
+        ];
+        assert_eq!(lagoon_area(&clockwise), 8);
+        assert_eq!(lagoon_area(&counter_clockwise), 8);
+    }
+
+    #[test]
+    fn test_parse_dig_plan_invalid() {
+        let err = parse_dig_plan("R 6 (#70c710)\nX 6 (#70c710)").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 2: `X 6 (#70c710)`");
+
+        assert!("R 6 #70c710".parse::<Instruction>().is_err());
+        assert!("R 6 (#70c71)".parse::<Instruction>().is_err());
+        assert!("R -6 (#70c710)".parse::<Instruction>().is_err());
+        assert!("R 6 (#70c710) x".parse::<Instruction>().is_err());
+        assert!(parse_dig_plan("R 6 (#70c714)").is_err());
+    }
+}
@@ -0,0 +1,603 @@
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+use crate::parse::{numbered_nonempty_lines, LineContext};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+/// Name of the workflow every part starts in.
+pub const START_WORKFLOW: &str = "in";
+/// Every rating of a part is within this range (part 2).
+pub const RATING_RANGE: Range<u64> = 1..4001;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (the workflows, an empty line, then the parts).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of all ratings of the accepted parts
+    pub part1: u64,
+    /// Number of distinct combinations of ratings that would be accepted
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!(
+        "(Part 1) Sum of the ratings of accepted parts: {}",
+        answers.part1
+    );
+    info!(
+        "(Part 2) Accepted combinations of ratings: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day19::{solve, Answers};
+/// let answers = solve("in{x<2:A,R}\n\n{x=1,m=2,a=3,s=4}\n{x=2,m=2,a=3,s=4}\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 10, part2: 4000 * 4000 * 4000 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let (workflows, parts) = parse_system(input)?;
+
+    let part1 = parts
+        .iter()
+        .filter(|part| is_accepted(&workflows, part))
+        .map(Part::total_rating)
+        .sum();
+    let part2 = count_accepted(&workflows);
+
+    Ok(Answers { part1, part2 })
+}
+
+/// One of the four categories a part is rated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Extremely cool looking
+    X,
+    /// Musical
+    M,
+    /// Aerodynamic
+    A,
+    /// Shiny
+    S,
+}
+
+impl Category {
+    /// Position of the category in [`Part::ratings`].
+    fn index(self) -> usize {
+        match self {
+            Category::X => 0,
+            Category::M => 1,
+            Category::A => 2,
+            Category::S => 3,
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = anyhow::Error;
+
+    fn from_str(category: &str) -> Result<Category> {
+        Ok(match category {
+            "x" => Category::X,
+            "m" => Category::M,
+            "a" => Category::A,
+            "s" => Category::S,
+            _ => bail!(
+                "Invalid category `{}`, expected `x`, `m`, `a` or `s`",
+                category
+            ),
+        })
+    }
+}
+
+/// A machine part, like `{x=787,m=2655,a=1222,s=2876}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Part {
+    /// Ratings in the order `x`, `m`, `a`, `s`
+    pub ratings: [u64; 4],
+}
+
+impl Part {
+    pub fn rating(&self, category: Category) -> u64 {
+        self.ratings[category.index()]
+    }
+
+    pub fn total_rating(&self) -> u64 {
+        self.ratings.iter().sum()
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    /// Parses a part like `{x=787,m=2655,a=1222,s=2876}`. Every category must be rated exactly
+    /// once, in any order.
+    fn from_str(part: &str) -> Result<Part> {
+        let ratings_str = part
+            .strip_prefix('{')
+            .and_then(|part| part.strip_suffix('}'))
+            .context("Expected the part to be in braces, like `{x=787,m=2655,a=1222,s=2876}`")?;
+
+        let mut ratings = [None; 4];
+        for rating in ratings_str.split(',') {
+            let (category_str, value) = rating
+                .split_once('=')
+                .with_context(|| format!("Expected a rating like `x=787`, got `{}`", rating))?;
+            let category = category_str.parse::<Category>()?;
+            let value = value
+                .parse::<u64>()
+                .with_context(|| format!("Invalid rating `{}`", value))?;
+            ensure!(
+                ratings[category.index()].replace(value).is_none(),
+                "The category `{}` is rated more than once",
+                category_str
+            );
+        }
+
+        let [Some(x), Some(m), Some(a), Some(s)] = ratings else {
+            bail!("Every category (`x`, `m`, `a` and `s`) must be rated");
+        };
+        Ok(Part {
+            ratings: [x, m, a, s],
+        })
+    }
+}
+
+/// Where a part goes once a rule applies to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+impl From<&str> for Target {
+    fn from(target: &str) -> Target {
+        match target {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            name => Target::Workflow(name.to_owned()),
+        }
+    }
+}
+
+/// A condition like `a<2006`, which a part has to meet for its rule to apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Condition {
+    pub category: Category,
+    /// Whether the rating has to be greater than `value` (`>`) instead of less than it (`<`)
+    pub greater_than: bool,
+    pub value: u64,
+}
+
+impl Condition {
+    pub fn matches(&self, part: &Part) -> bool {
+        let rating = part.rating(self.category);
+        if self.greater_than {
+            rating > self.value
+        } else {
+            rating < self.value
+        }
+    }
+
+    /// Splits the range of ratings (in this condition's category) into the ratings that meet
+    /// the condition and those that don't. Either of them may be empty.
+    fn split(&self, ratings: &Range<u64>) -> (Range<u64>, Range<u64>) {
+        if self.greater_than {
+            let threshold = self
+                .value
+                .saturating_add(1)
+                .clamp(ratings.start, ratings.end);
+            (threshold..ratings.end, ratings.start..threshold)
+        } else {
+            let threshold = self.value.clamp(ratings.start, ratings.end);
+            (ratings.start..threshold, threshold..ratings.end)
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = anyhow::Error;
+
+    fn from_str(condition: &str) -> Result<Condition> {
+        let Some(op_idx) = condition.find(['<', '>']) else {
+            bail!("Expected a condition like `a<2006`, got `{}`", condition);
+        };
+        let category = condition[..op_idx].parse::<Category>()?;
+        let greater_than = condition[op_idx..].starts_with('>');
+        let value = &condition[op_idx + 1..];
+        let value = value
+            .parse::<u64>()
+            .with_context(|| format!("Invalid value `{}` in the condition", value))?;
+
+        Ok(Condition {
+            category,
+            greater_than,
+            value,
+        })
+    }
+}
+
+/// A rule like `a<2006:qkq`. Rules without a condition (like `R`) always apply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub condition: Option<Condition>,
+    pub target: Target,
+}
+
+impl FromStr for Rule {
+    type Err = anyhow::Error;
+
+    fn from_str(rule: &str) -> Result<Rule> {
+        let Some((condition, target)) = rule.split_once(':') else {
+            return Ok(Rule {
+                condition: None,
+                target: Target::from(rule),
+            });
+        };
+        Ok(Rule {
+            condition: Some(condition.parse()?),
+            target: Target::from(target),
+        })
+    }
+}
+
+/// A named workflow like `px{a<2006:qkq,m>2090:A,rfg}`. The rules are checked in order, the last
+/// one has no condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflow {
+    pub name: String,
+    pub rules: Vec<Rule>,
+}
+
+impl Workflow {
+    /// Where the part goes next: the target of the first rule that applies to it.
+    pub fn target_for(&self, part: &Part) -> &Target {
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.condition
+                    .is_none_or(|condition| condition.matches(part))
+            })
+            .map(|rule| &rule.target)
+            .expect("The last rule has no condition")
+    }
+}
+
+impl FromStr for Workflow {
+    type Err = anyhow::Error;
+
+    fn from_str(workflow: &str) -> Result<Workflow> {
+        let (name, rules) = workflow
+            .strip_suffix('}')
+            .and_then(|workflow| workflow.split_once('{'))
+            .context("Expected a workflow like `px{a<2006:qkq,m>2090:A,rfg}`")?;
+        ensure!(!name.is_empty(), "The workflow has no name");
+
+        let rules = rules
+            .split(',')
+            .map(str::parse::<Rule>)
+            .collect::<Result<Vec<_>>>()?;
+        match rules.last() {
+            Some(rule) if rule.condition.is_none() => {}
+            _ => bail!("The last rule of a workflow must not have a condition"),
+        }
+        ensure!(
+            rules[..rules.len() - 1]
+                .iter()
+                .all(|rule| rule.condition.is_some()),
+            "Only the last rule of a workflow may be without a condition"
+        );
+
+        Ok(Workflow {
+            name: name.to_owned(),
+            rules,
+        })
+    }
+}
+
+/// Parses the workflows (by name) and the parts, separated by an empty line.
+///
+/// Fails unless there is an `in` workflow, every target workflow exists and no part could be
+/// sent around in circles.
+pub fn parse_system(input: &str) -> Result<(HashMap<String, Workflow>, Vec<Part>)> {
+    let mut workflows = HashMap::new();
+    let mut parts = vec![];
+    // Line number of the (first) empty line between the workflows and the parts
+    let separator = input
+        .lines()
+        .position(|line| line.trim().is_empty())
+        .map_or(usize::MAX, |line_idx| line_idx + 1);
+
+    for (line, content) in numbered_nonempty_lines(input) {
+        if line < separator {
+            let workflow = content
+                .parse::<Workflow>()
+                .with_context(|| LineContext::new(line, content))?;
+            ensure!(
+                !workflows.contains_key(&workflow.name),
+                "The workflow `{}` on line {} is defined more than once",
+                workflow.name,
+                line
+            );
+            workflows.insert(workflow.name.clone(), workflow);
+        } else if !content.trim().is_empty() {
+            let part = content
+                .parse::<Part>()
+                .with_context(|| LineContext::new(line, content))?;
+            parts.push(part);
+        }
+    }
+
+    validate_workflows(&workflows)?;
+    Ok((workflows, parts))
+}
+
+/// Checks that the `in` workflow and every target workflow exist, and that the workflows
+/// don't form a cycle.
+fn validate_workflows(workflows: &HashMap<String, Workflow>) -> Result<()> {
+    ensure!(
+        workflows.contains_key(START_WORKFLOW),
+        "There is no `{}` workflow",
+        START_WORKFLOW
+    );
+    for workflow in workflows.values() {
+        for rule in &workflow.rules {
+            if let Target::Workflow(name) = &rule.target {
+                ensure!(
+                    workflows.contains_key(name),
+                    "The workflow `{}` sends parts to the unknown workflow `{}`",
+                    workflow.name,
+                    name
+                );
+            }
+        }
+    }
+
+    let mut done = HashSet::new();
+    for name in workflows.keys() {
+        check_for_cycles(workflows, name, &mut vec![], &mut done)?;
+    }
+    Ok(())
+}
+
+/// Depth-first search from the given workflow, failing if it leads back to a workflow on
+/// `on_path` (the ones currently being visited). Workflows in `done` are known to be fine.
+fn check_for_cycles<'a>(
+    workflows: &'a HashMap<String, Workflow>,
+    name: &'a str,
+    on_path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+) -> Result<()> {
+    if done.contains(name) {
+        return Ok(());
+    }
+    ensure!(
+        !on_path.contains(&name),
+        "The workflows form a cycle: {} -> {}",
+        on_path.join(" -> "),
+        name
+    );
+    on_path.push(name);
+    for rule in &workflows[name].rules {
+        if let Target::Workflow(next) = &rule.target {
+            check_for_cycles(workflows, next, on_path, done)?;
+        }
+    }
+    on_path.pop();
+    done.insert(name);
+    Ok(())
+}
+
+/// Whether the part is accepted after running it through the workflows, starting at `in`.
+///
+/// The workflows must have been checked by [`parse_system`] (every target exists, no cycles).
+pub fn is_accepted(workflows: &HashMap<String, Workflow>, part: &Part) -> bool {
+    let mut workflow = &workflows[START_WORKFLOW];
+    loop {
+        match workflow.target_for(part) {
+            Target::Accept => return true,
+            Target::Reject => return false,
+            Target::Workflow(name) => workflow = &workflows[name],
+        }
+    }
+}
+
+/// Counts how many combinations of ratings (each from 1 to 4000) would be accepted.
+///
+/// Instead of trying every combination, the four ranges of ratings are split by each rule:
+/// the ratings that meet its condition go on to its target, the others go on to the next rule.
+///
+/// The workflows must have been checked by [`parse_system`] (every target exists, no cycles).
+pub fn count_accepted(workflows: &HashMap<String, Workflow>) -> u64 {
+    let ratings = [0; 4].map(|_| RATING_RANGE);
+    count_accepted_in(
+        workflows,
+        &Target::Workflow(START_WORKFLOW.to_owned()),
+        ratings,
+    )
+}
+
+/// Counts the accepted combinations within the given ranges of ratings, when sent to `target`.
+fn count_accepted_in(
+    workflows: &HashMap<String, Workflow>,
+    target: &Target,
+    mut ratings: [Range<u64>; 4],
+) -> u64 {
+    let name = match target {
+        Target::Accept => {
+            return ratings
+                .iter()
+                .map(|range| range.end - range.start)
+                .product()
+        }
+        Target::Reject => return 0,
+        Target::Workflow(name) => name,
+    };
+
+    let mut accepted = 0;
+    for rule in &workflows[name].rules {
+        let Some(condition) = rule.condition else {
+            return accepted + count_accepted_in(workflows, &rule.target, ratings);
+        };
+
+        let index = condition.category.index();
+        let (matching, remaining) = condition.split(&ratings[index]);
+        if !matching.is_empty() {
+            let mut matching_ratings = ratings.clone();
+            matching_ratings[index] = matching;
+            accepted += count_accepted_in(workflows, &rule.target, matching_ratings);
+        }
+        if remaining.is_empty() {
+            return accepted;
+        }
+        ratings[index] = remaining;
+    }
+
+    unreachable!("The last rule has no condition")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 19114,
+                part2: 167409079868000
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_accepted_example_data() {
+        let (workflows, parts) = parse_system(EXAMPLE_INPUT).unwrap();
+        let accepted = parts
+            .iter()
+            .map(|part| is_accepted(&workflows, part))
+            .collect::<Vec<_>>();
+        assert_eq!(accepted, vec![true, false, true, false, true]);
+    }
+
+    #[test]
+    fn test_condition_split() {
+        let less_than = "a<2006".parse::<Condition>().unwrap();
+        assert_eq!(less_than.split(&(1..4001)), (1..2006, 2006..4001));
+        assert_eq!(less_than.split(&(3000..4001)), (3000..3000, 3000..4001));
+
+        let greater_than = "m>2090".parse::<Condition>().unwrap();
+        assert_eq!(greater_than.split(&(1..4001)), (2091..4001, 1..2091));
+        assert_eq!(greater_than.split(&(1..100)), (100..100, 1..100));
+    }
+
+    #[test]
+    fn test_parse_part() {
+        let part = "{s=4,a=3,m=2,x=1}".parse::<Part>().unwrap();
+        assert_eq!(part.ratings, [1, 2, 3, 4]);
+        assert_eq!(part.rating(Category::A), 3);
+
+        assert!("{x=1,m=2,a=3}".parse::<Part>().is_err());
+        assert!("{x=1,m=2,a=3,s=4,x=5}".parse::<Part>().is_err());
+        assert!("x=1,m=2,a=3,s=4".parse::<Part>().is_err());
+        assert!("{x=1,m=2,a=3,y=4}".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn test_parse_workflow_invalid() {
+        assert!("px{a<2006:qkq}".parse::<Workflow>().is_err());
+        assert!("px{A,a<2006:qkq,R}".parse::<Workflow>().is_err());
+        assert!("px{a=2006:qkq,R}".parse::<Workflow>().is_err());
+        assert!("{R}".parse::<Workflow>().is_err());
+    }
+
+    #[test]
+    fn test_parse_system_invalid() {
+        let err = parse_system("px{A}\n\n").unwrap_err();
+        assert_eq!(err.to_string(), "There is no `in` workflow");
+
+        let err = parse_system("in{x<5:px,R}\n\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The workflow `in` sends parts to the unknown workflow `px`"
+        );
+
+        let err = parse_system("in{x<5:px,R}\npx{in}\n\n").unwrap_err();
+        assert!(
+            err.to_string().starts_with("The workflows form a cycle"),
+            "{}",
+            err
+        );
+
+        let err = parse_system("in{A}\n\n{x=1}").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 3: `{x=1}`");
+        let err = parse_system("in{A}\npx{A,R}\n\n{x=1,m=2,a=3,s=4}").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 2: `px{A,R}`");
+    }
+}
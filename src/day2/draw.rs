@@ -1,8 +1,10 @@
 use anyhow::{bail, ensure, Context, Result};
+use std::cmp::Ordering;
 use std::str::FromStr;
 
 /// Subset of cubes that were revealed from the bag
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Draw {
     /// Number of red cubes in this draw
     pub num_red: u8,
@@ -12,6 +14,95 @@ pub struct Draw {
     pub num_blue: u8,
 }
 
+/// One of the three colors of cubes in the bag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+impl Color {
+    /// All colors, in the order they are usually listed in.
+    pub const ALL: [Color; 3] = [Color::Red, Color::Green, Color::Blue];
+
+    /// The name of the color, as it is written in the puzzle input.
+    pub fn name(self) -> &'static str {
+        match self {
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Blue => "blue",
+        }
+    }
+}
+
+impl Draw {
+    /// Number of cubes of the given color in this draw.
+    pub fn num(&self, color: Color) -> u8 {
+        match color {
+            Color::Red => self.num_red,
+            Color::Green => self.num_green,
+            Color::Blue => self.num_blue,
+        }
+    }
+
+    /// Total number of cubes in this draw, across all colors.
+    pub fn total_cubes(&self) -> u32 {
+        self.num_red as u32 + self.num_green as u32 + self.num_blue as u32
+    }
+
+    /// Returns the color of which the most cubes were drawn, or `None` if no cubes were drawn.
+    /// Ties are broken in favor of red, then green, then blue.
+    pub fn max_color(&self) -> Option<Color> {
+        Color::ALL
+            .into_iter()
+            .filter(|&color| self.num(color) > 0)
+            // `max_by_key` returns the last maximum, but ties should go to the first one
+            .rev()
+            .max_by_key(|&color| self.num(color))
+    }
+
+    /// Caps the number of cubes of every color at the number in `max`, e.g. to see what could
+    /// have been drawn from a smaller bag. The result is always `<= max`.
+    pub fn clamp(&self, max: Draw) -> Draw {
+        Draw {
+            num_red: u8::min(self.num_red, max.num_red),
+            num_green: u8::min(self.num_green, max.num_green),
+            num_blue: u8::min(self.num_blue, max.num_blue),
+        }
+    }
+
+    /// Mutable version of [`Draw::num`].
+    pub fn num_mut(&mut self, color: Color) -> &mut u8 {
+        match color {
+            Color::Red => &mut self.num_red,
+            Color::Green => &mut self.num_green,
+            Color::Blue => &mut self.num_blue,
+        }
+    }
+}
+
+/// Draws are ordered by dominance: `a <= b` if `a` has at most as many cubes as `b` of every
+/// color, e.g. if draw `a` was possible with the bag contents `b`. Draws where each has more
+/// cubes of some color than the other are incomparable (`None`).
+impl PartialOrd for Draw {
+    fn partial_cmp(&self, other: &Draw) -> Option<Ordering> {
+        let less_or_equal = Color::ALL
+            .into_iter()
+            .all(|color| self.num(color) <= other.num(color));
+        let greater_or_equal = Color::ALL
+            .into_iter()
+            .all(|color| self.num(color) >= other.num(color));
+
+        match (less_or_equal, greater_or_equal) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
 impl FromStr for Draw {
     type Err = anyhow::Error;
 
@@ -68,3 +159,143 @@ impl FromStr for Draw {
         Ok(draw)
     }
 }
+
+impl TryFrom<&str> for Draw {
+    type Error = anyhow::Error;
+
+    /// Same as the [`FromStr`] implementation, for generic code with `TryFrom` bounds.
+    fn try_from(input: &str) -> Result<Draw> {
+        input.parse()
+    }
+}
+
+/// Formats a draw the same way it appears in the puzzle input, e.g. `4 red, 2 green, 6 blue`.
+///
+/// Colors are always spelled out in full and emitted in red, green, blue order, no matter how
+/// the draw was originally written. Colors of which zero cubes were drawn are left out.
+/// Exporters should use this so that their output is stable.
+pub fn canonical_draw_string(draw: &Draw) -> String {
+    Color::ALL
+        .into_iter()
+        .filter(|&color| draw.num(color) > 0)
+        .map(|color| format!("{} {}", draw.num(color), color.name()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_try_from_matches_parse() {
+        assert_eq!(
+            Draw::try_from("3 blue, 4 red").unwrap(),
+            "3 blue, 4 red".parse::<Draw>().unwrap()
+        );
+        assert!(Draw::try_from("3 purple").is_err());
+    }
+
+    #[test]
+    fn test_canonical_draw_string_reorders_colors() {
+        let draw = "6 blue, 2 green, 4 red".parse::<Draw>().unwrap();
+        assert_eq!(canonical_draw_string(&draw), "4 red, 2 green, 6 blue");
+    }
+
+    #[test]
+    fn test_canonical_draw_string_omits_zero_colors() {
+        let draw = "3 blue, 1 red".parse::<Draw>().unwrap();
+        assert_eq!(canonical_draw_string(&draw), "1 red, 3 blue");
+        assert_eq!(
+            canonical_draw_string(&Draw {
+                num_red: 0,
+                num_green: 7,
+                num_blue: 0
+            }),
+            "7 green"
+        );
+    }
+
+    #[test]
+    fn test_canonical_draw_string_round_trips() {
+        let draw = Draw {
+            num_red: 12,
+            num_green: 1,
+            num_blue: 255,
+        };
+        assert_eq!(canonical_draw_string(&draw).parse::<Draw>().unwrap(), draw);
+    }
+
+    #[test]
+    fn test_max_color() {
+        let draw = "3 blue, 4 red".parse::<Draw>().unwrap();
+        assert_eq!(draw.max_color(), Some(Color::Red));
+        let draw = "1 red, 2 green, 6 blue".parse::<Draw>().unwrap();
+        assert_eq!(draw.max_color(), Some(Color::Blue));
+        let draw = "2 green".parse::<Draw>().unwrap();
+        assert_eq!(draw.max_color(), Some(Color::Green));
+        assert_eq!(Draw::default().max_color(), None);
+    }
+
+    #[test]
+    fn test_max_color_ties() {
+        let draw = "5 blue, 5 red, 5 green".parse::<Draw>().unwrap();
+        assert_eq!(draw.max_color(), Some(Color::Red));
+        let draw = "5 blue, 1 red, 5 green".parse::<Draw>().unwrap();
+        assert_eq!(draw.max_color(), Some(Color::Green));
+        let draw = "5 blue, 5 red".parse::<Draw>().unwrap();
+        assert_eq!(draw.max_color(), Some(Color::Red));
+    }
+
+    #[test]
+    fn test_partial_cmp_dominance() {
+        let draw = |num_red, num_green, num_blue| Draw {
+            num_red,
+            num_green,
+            num_blue,
+        };
+        assert_eq!(
+            draw(1, 2, 3).partial_cmp(&draw(2, 3, 4)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            draw(2, 3, 4).partial_cmp(&draw(1, 2, 3)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(draw(1, 5, 3).partial_cmp(&draw(2, 3, 4)), None);
+        assert_eq!(
+            draw(1, 2, 3).partial_cmp(&draw(1, 2, 3)),
+            Some(Ordering::Equal)
+        );
+
+        // Only some components being smaller is still smaller, if none is larger
+        assert!(draw(1, 3, 4) < draw(2, 3, 4));
+        assert!(draw(1, 5, 3) > draw(1, 5, 0));
+        // Incomparable draws are neither smaller nor larger
+        let (a, b) = (draw(1, 5, 3), draw(2, 3, 4));
+        assert!(!a.le(&b) && !a.ge(&b));
+    }
+
+    #[test]
+    fn test_total_cubes() {
+        let draw = Draw {
+            num_red: 4,
+            num_green: 2,
+            num_blue: 6,
+        };
+        assert_eq!(draw.total_cubes(), 12);
+        assert_eq!(Draw::default().total_cubes(), 0);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let draw = |num_red, num_green, num_blue| Draw {
+            num_red,
+            num_green,
+            num_blue,
+        };
+        assert_eq!(draw(5, 20, 3).clamp(draw(4, 14, 16)), draw(4, 14, 3));
+        assert_eq!(draw(5, 20, 3).clamp(draw(5, 20, 3)), draw(5, 20, 3));
+        assert_eq!(draw(5, 20, 3).clamp(Draw::default()), Draw::default());
+    }
+}
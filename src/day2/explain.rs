@@ -0,0 +1,108 @@
+use crate::day2::{Color, Draw, Game};
+use std::fmt::Write;
+
+/// Walks through [`Game::minimum_bag_contents`] step by step and explains how its power comes
+/// about: For every draw, the element-wise maximum with the bag contents so far, and finally
+/// the product of the three colors.
+///
+/// For example, for `Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red`:
+///
+/// ```text
+/// Game 3
+/// ├─ draw 1: 20 red, 8 green, 6 blue
+/// │  ├─ red:   max(0, 20) = 20
+/// ...
+/// └─ power = 20 * 13 * 6 = 1560
+/// ```
+pub fn explain_power(game: &Game) -> String {
+    let mut explanation = String::new();
+    // Writing to a `String` cannot fail, hence the `unwrap()`s
+    writeln!(explanation, "Game {}", game.id).unwrap();
+
+    let mut bag = Draw::default();
+    for (draw_idx, draw) in game.draws.iter().enumerate() {
+        writeln!(
+            explanation,
+            "├─ draw {}: {} red, {} green, {} blue",
+            draw_idx + 1,
+            draw.num_red,
+            draw.num_green,
+            draw.num_blue
+        )
+        .unwrap();
+
+        let mut next_bag = bag;
+        for (color_idx, color) in Color::ALL.into_iter().enumerate() {
+            let max = u8::max(bag.num(color), draw.num(color));
+            *next_bag.num_mut(color) = max;
+
+            let branch = if color_idx + 1 == Color::ALL.len() {
+                "└─"
+            } else {
+                "├─"
+            };
+            writeln!(
+                explanation,
+                "│  {} {:<6} max({}, {}) = {}",
+                branch,
+                format!("{}:", color.name()),
+                bag.num(color),
+                draw.num(color),
+                max
+            )
+            .unwrap();
+        }
+        bag = next_bag;
+    }
+
+    debug_assert_eq!(bag, game.minimum_bag_contents());
+    writeln!(
+        explanation,
+        "├─ minimum bag: {} red, {} green, {} blue",
+        bag.num_red, bag.num_green, bag.num_blue
+    )
+    .unwrap();
+    writeln!(
+        explanation,
+        "└─ power = {} * {} * {} = {}",
+        bag.num_red,
+        bag.num_green,
+        bag.num_blue,
+        game.calculate_power()
+    )
+    .unwrap();
+
+    explanation
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_explain_power_example_game3() {
+        let game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
+            .parse::<Game>()
+            .unwrap();
+        assert_eq!(
+            explain_power(&game),
+            "\
+Game 3
+├─ draw 1: 20 red, 8 green, 6 blue
+│  ├─ red:   max(0, 20) = 20
+│  ├─ green: max(0, 8) = 8
+│  └─ blue:  max(0, 6) = 6
+├─ draw 2: 4 red, 13 green, 5 blue
+│  ├─ red:   max(20, 4) = 20
+│  ├─ green: max(8, 13) = 13
+│  └─ blue:  max(6, 5) = 6
+├─ draw 3: 1 red, 5 green, 0 blue
+│  ├─ red:   max(20, 1) = 20
+│  ├─ green: max(13, 5) = 13
+│  └─ blue:  max(6, 0) = 6
+├─ minimum bag: 20 red, 13 green, 6 blue
+└─ power = 20 * 13 * 6 = 1560
+"
+        );
+    }
+}
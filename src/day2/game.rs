@@ -1,4 +1,5 @@
-use crate::day2::Draw;
+use crate::day2::{Color, Draw};
+use crate::regex_profile;
 use anyhow::{Context, Result};
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -6,17 +7,35 @@ use std::str::FromStr;
 
 /// A single game of draw-the-cubes.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     pub id: u64,
     /// List of subsets of cubes that were revealed from the bag
     pub draws: Vec<Draw>,
 }
 
+/// Explains why a game was impossible: The first time more cubes of a color were drawn
+/// than there were in the bag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImpossibleReason {
+    /// Index of the offending draw within [`Game::draws`]
+    pub draw_idx: usize,
+    pub color: Color,
+    /// Number of cubes of `color` in that draw
+    pub drawn: u8,
+    /// Number of cubes of `color` in the bag
+    pub available: u8,
+}
+
 lazy_static! {
     // https://regex101.com/r/bccoKD/1
     // Capture group 1 = Game ID
     // Capture group 2 = Unparsed List of Draws (ensures somewhat proper format though)
     static ref GAME_STR_FORMAT: Regex = Regex::new(r"^Game (\d+): ((?:\d+ (?:red|green|blue)(?:[,;] )?)+)$").unwrap();
+
+    // Any whitespace around a separator, used to normalize it to the `X ` format
+    static ref SEPARATOR_WHITESPACE: Regex = Regex::new(r"\s*([,;:])\s*").unwrap();
+    static ref ANY_WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
 }
 
 impl FromStr for Game {
@@ -24,8 +43,9 @@ impl FromStr for Game {
 
     /// Parses a string like `Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green`
     fn from_str(input: &str) -> Result<Game> {
-        let captures = GAME_STR_FORMAT
-            .captures(input)
+        // The regex is anchored with `$`, so a `\r` left over from CRLF line endings would not match
+        let input = input.trim_end_matches('\r');
+        let captures = regex_profile::captures(&GAME_STR_FORMAT, input)
             .with_context(|| format!("Game `{}` is of invalid format", input))?;
 
         let game_id = captures
@@ -42,23 +62,116 @@ impl FromStr for Game {
             .collect::<Result<Vec<Draw>>>()
             .with_context(|| format!("A draw in game `{}` has an invalid format", input))?;
 
-        Ok(Game { id: game_id, draws })
+        Ok(Game::new(game_id, draws))
+    }
+}
+
+impl TryFrom<&str> for Game {
+    type Error = anyhow::Error;
+
+    /// Same as the [`FromStr`] implementation, for generic code with `TryFrom` bounds.
+    fn try_from(input: &str) -> Result<Game> {
+        input.parse()
     }
 }
 
 impl Game {
+    /// A game with the given ID and draws, e.g. for tests or generated games.
+    /// A game without draws is allowed: it is possible with any bag, and its
+    /// [`minimum_bag_contents`](Game::minimum_bag_contents) are empty.
+    pub fn new(id: u64, draws: Vec<Draw>) -> Game {
+        Game { id, draws }
+    }
+
+    /// Like the [`FromStr`] implementation, but tolerates extra whitespace and empty draws, e.g.
+    /// `Game  1 : 3 blue ,\t4 red;1 red ;`. Any run of whitespace is collapsed into a single
+    /// space, separators (`:`, `,` and `;`) are normalized to be followed by exactly one
+    /// space, and empty draws (like the one after a trailing `;`) are dropped, before the game
+    /// is parsed as usual. Games that are malformed in other ways are still rejected.
+    pub fn parse_lenient(input: &str) -> Result<Game> {
+        drop_empty_draws(&normalize_whitespace(input)).parse::<Game>()
+    }
+
     /// Returns whether this game's draws had been theoretically possible if the given number of
     /// red, green and blue cubes were in a bag.
     pub fn was_possible(&self, max_red: u8, max_green: u8, max_blue: u8) -> bool {
-        self.draws.iter().all(|draw| {
-            draw.num_red <= max_red && draw.num_green <= max_green && draw.num_blue <= max_blue
+        let bag = Draw {
+            num_red: max_red,
+            num_green: max_green,
+            num_blue: max_blue,
+        };
+        self.draws.iter().all(|draw| *draw <= bag)
+    }
+
+    /// Returns a copy of this game with every draw [clamped](Draw::clamp) to the given `bag`,
+    /// which makes it [possible](Game::was_possible) with that bag.
+    pub fn clamp_to_bag(&self, bag: Draw) -> Game {
+        Game::new(
+            self.id,
+            self.draws.iter().map(|draw| draw.clamp(bag)).collect(),
+        )
+    }
+
+    /// Like [`Game::was_possible`], but instead explains why the game was impossible.
+    /// Returns `None` if the game was possible.
+    ///
+    /// If multiple draws or colors exceed the bag, the first draw and first color
+    /// (in red, green, blue order) is reported.
+    pub fn impossible_reason(
+        &self,
+        max_red: u8,
+        max_green: u8,
+        max_blue: u8,
+    ) -> Option<ImpossibleReason> {
+        let bag = Draw {
+            num_red: max_red,
+            num_green: max_green,
+            num_blue: max_blue,
+        };
+
+        self.draws.iter().enumerate().find_map(|(draw_idx, draw)| {
+            let color = Color::ALL
+                .into_iter()
+                .find(|&color| draw.num(color) > bag.num(color))?;
+            Some(ImpossibleReason {
+                draw_idx,
+                color,
+                drawn: draw.num(color),
+                available: bag.num(color),
+            })
         })
     }
 
+    /// Returns how many draws have to be observed before this game is provably impossible with
+    /// the given `bag`, i.e. the 1-based position of the first draw that exceeds it.
+    /// Returns `None` if the game was possible.
+    pub fn draws_until_impossible(&self, bag: &Draw) -> Option<usize> {
+        self.impossible_reason(bag.num_red, bag.num_green, bag.num_blue)
+            .map(|reason| reason.draw_idx + 1)
+    }
+
+    /// A compact single-line summary for dense logging, like
+    /// `Game 3: possible=false power=1560 draws=3`, where `possible` is with the given `bag`.
+    pub fn summary(&self, bag: &Draw) -> String {
+        format!(
+            "Game {}: possible={} power={} draws={}",
+            self.id,
+            self.was_possible(bag.num_red, bag.num_green, bag.num_blue),
+            self.calculate_power(),
+            self.draws.len()
+        )
+    }
+
+    /// Total number of cubes revealed in this game, summed over all of its draws.
+    pub fn total_cubes(&self) -> u32 {
+        self.draws.iter().map(Draw::total_cubes).sum()
+    }
+
     /// Given the draws in this game, finds what amount of cubes would have had
     /// to be in the bag for all draws in this game to be possible.
     ///
-    /// Panics if this game has no draws.
+    /// A game without draws needs no cubes at all, so this is [`Draw::default`] (and the
+    /// [power](Game::calculate_power) is 0).
     pub fn minimum_bag_contents(&self) -> Draw {
         self.draws
             .iter()
@@ -79,10 +192,62 @@ impl Game {
     }
 }
 
+/// Removes a trailing `#` comment (and the whitespace before it) from a line, e.g. turns
+/// `Game 1: 3 blue, 4 red  # seen twice` into `Game 1: 3 blue, 4 red`.
+/// Lines without a comment are returned as they are.
+pub fn strip_comment(line: &str) -> &str {
+    match line.split_once('#') {
+        Some((content, _comment)) => content.trim_end(),
+        None => line,
+    }
+}
+
+/// Applies [`strip_comment`] to every line of `input`. Lines that only contain a comment end up
+/// empty, so that they are skipped like other empty lines. Line numbers stay the same.
+pub fn strip_comments(input: &str) -> String {
+    input
+        .lines()
+        .map(|line| format!("{}\n", strip_comment(line)))
+        .collect()
+}
+
+/// Normalizes the whitespace in a game, see [`Game::parse_lenient`].
+fn normalize_whitespace(input: &str) -> String {
+    let separators_normalized = SEPARATOR_WHITESPACE.replace_all(input.trim(), "$1 ");
+    ANY_WHITESPACE
+        .replace_all(separators_normalized.trim(), " ")
+        .into_owned()
+}
+
+/// Removes empty draws from a game with normalized whitespace, e.g. turns
+/// `Game 1: ; 3 blue; ; 4 red;` into `Game 1: 3 blue; 4 red`.
+fn drop_empty_draws(input: &str) -> String {
+    let Some((game_id, draws)) = input.split_once(':') else {
+        // Not a game at all, leave it to the parser to reject it
+        return input.to_string();
+    };
+
+    let draws = draws
+        .split(';')
+        .map(str::trim)
+        .filter(|draw| !draw.is_empty())
+        .collect::<Vec<_>>();
+    format!("{}: {}", game_id, draws.join("; "))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_try_from_matches_parse() {
+        assert_eq!(
+            Game::try_from("Game 1: 1 red").unwrap(),
+            "Game 1: 1 red".parse::<Game>().unwrap()
+        );
+        assert!(Game::try_from("Game x: 1 red").is_err());
+    }
+
     #[test]
     fn test_parse_game_example_data_game1() {
         let game_str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
@@ -450,4 +615,229 @@ mod test {
         );
         assert_eq!(game.calculate_power(), 0);
     }
+
+    #[test]
+    fn test_new_matches_parse() {
+        let game = Game::new(
+            7,
+            vec![
+                Draw {
+                    num_red: 4,
+                    num_green: 0,
+                    num_blue: 3,
+                },
+                Draw {
+                    num_red: 1,
+                    num_green: 2,
+                    num_blue: 6,
+                },
+            ],
+        );
+        assert_eq!(
+            game,
+            "Game 7: 3 blue, 4 red; 1 red, 2 green, 6 blue"
+                .parse::<Game>()
+                .unwrap()
+        );
+        assert_eq!(game.calculate_power(), 4 * 2 * 6);
+
+        // A game without draws is possible with any bag
+        let game = Game::new(100, vec![]);
+        assert!(game.was_possible(0, 0, 0));
+    }
+
+    #[test]
+    fn test_impossible_reason() {
+        let game = "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red"
+            .parse::<Game>()
+            .unwrap();
+        assert_eq!(
+            game.impossible_reason(12, 13, 14),
+            Some(ImpossibleReason {
+                draw_idx: 2,
+                color: Color::Red,
+                drawn: 14,
+                available: 12,
+            })
+        );
+        assert_eq!(
+            game.impossible_reason(14, 13, 14),
+            Some(ImpossibleReason {
+                draw_idx: 2,
+                color: Color::Blue,
+                drawn: 15,
+                available: 14,
+            })
+        );
+        assert_eq!(game.impossible_reason(14, 13, 15), None);
+    }
+
+    #[test]
+    fn test_summary_example_game3() {
+        let game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
+            .parse::<Game>()
+            .unwrap();
+        let bag = Draw {
+            num_red: 12,
+            num_green: 13,
+            num_blue: 14,
+        };
+        assert_eq!(
+            game.summary(&bag),
+            "Game 3: possible=false power=1560 draws=3"
+        );
+
+        let bag = Draw { num_red: 20, ..bag };
+        assert_eq!(
+            game.summary(&bag),
+            "Game 3: possible=true power=1560 draws=3"
+        );
+    }
+
+    #[test]
+    fn test_total_cubes_example_game1() {
+        let game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+            .parse::<Game>()
+            .unwrap();
+        assert_eq!(game.total_cubes(), (3 + 4) + (1 + 2 + 6) + 2);
+    }
+
+    #[test]
+    fn test_draws_until_impossible() {
+        let game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
+            .parse::<Game>()
+            .unwrap();
+        let bag = |num_red, num_green, num_blue| Draw {
+            num_red,
+            num_green,
+            num_blue,
+        };
+        // The first draw already has too many red cubes
+        assert_eq!(game.draws_until_impossible(&bag(12, 13, 14)), Some(1));
+        // Only the second draw has too many green cubes
+        assert_eq!(game.draws_until_impossible(&bag(20, 12, 14)), Some(2));
+        assert_eq!(game.draws_until_impossible(&bag(20, 13, 6)), None);
+    }
+
+    #[test]
+    fn test_clamp_to_bag() {
+        let game = "Game 7: 5 red, 20 green, 3 blue; 1 red"
+            .parse::<Game>()
+            .unwrap();
+        let bag = Draw {
+            num_red: 4,
+            num_green: 14,
+            num_blue: 16,
+        };
+        assert!(!game.was_possible(4, 14, 16));
+
+        let clamped = game.clamp_to_bag(bag);
+        assert_eq!(clamped.id, 7);
+        assert_eq!(
+            clamped.draws,
+            vec![
+                Draw {
+                    num_red: 4,
+                    num_green: 14,
+                    num_blue: 3
+                },
+                Draw {
+                    num_red: 1,
+                    num_green: 0,
+                    num_blue: 0
+                }
+            ]
+        );
+        assert!(clamped.was_possible(4, 14, 16));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+            .parse::<Game>()
+            .unwrap();
+
+        let json = serde_json::to_value(&game).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "id": 1,
+                "draws": [
+                    {"num_red": 4, "num_green": 0, "num_blue": 3},
+                    {"num_red": 1, "num_green": 2, "num_blue": 6},
+                    {"num_red": 0, "num_green": 2, "num_blue": 0},
+                ]
+            })
+        );
+        assert_eq!(serde_json::from_value::<Game>(json).unwrap(), game);
+    }
+
+    #[test]
+    fn test_parse_game_trailing_carriage_return() {
+        let game_str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        assert_eq!(
+            format!("{}\r", game_str).parse::<Game>().unwrap(),
+            game_str.parse::<Game>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient_extra_whitespace() {
+        let canonical = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+            .parse::<Game>()
+            .unwrap();
+
+        for input in [
+            "Game 1: 3 blue , 4 red ; 1 red , 2 green , 6 blue ; 2 green",
+            "Game 1:\t3 blue,\t4 red;\t1 red,\t2 green,\t6 blue;\t2 green",
+            "Game\t1 :3  blue,4 red;1 red,2\tgreen,  6 blue ;2 green  ",
+        ] {
+            assert_eq!(Game::parse_lenient(input).unwrap(), canonical, "{}", input);
+            assert!(input.parse::<Game>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient_empty_draws() {
+        let canonical = "Game 1: 3 blue, 4 red; 2 green".parse::<Game>().unwrap();
+
+        for input in [
+            "Game 1:  3 blue ,4 red ; 2 green;",
+            "Game 1: 3 blue, 4 red;; 2 green",
+            "Game 1: ; 3 blue, 4 red ; ;2 green ; ",
+        ] {
+            assert_eq!(Game::parse_lenient(input).unwrap(), canonical, "{}", input);
+            assert!(input.parse::<Game>().is_err(), "{}", input);
+        }
+
+        // At least one draw is still required
+        assert!(Game::parse_lenient("Game 1: ;").is_err());
+    }
+
+    #[test]
+    fn test_parse_with_trailing_comment() {
+        let clean = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let commented = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green  # seen twice";
+
+        assert!(commented.parse::<Game>().is_err());
+        assert_eq!(
+            strip_comment(commented).parse::<Game>().unwrap(),
+            clean.parse::<Game>().unwrap()
+        );
+        assert_eq!(strip_comment(clean), clean);
+        assert_eq!(strip_comment("Game 1: 3 blue#x\r"), "Game 1: 3 blue");
+        assert_eq!(
+            strip_comments("# note\nGame 1: 3 blue # x\n   # indented\n"),
+            "\nGame 1: 3 blue\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient_still_rejects_malformed_games() {
+        assert!(Game::parse_lenient("Game 1: 3 blue 4 red").is_err());
+        assert!(Game::parse_lenient("Game 1: 3 blue,, 4 red").is_err());
+        assert!(Game::parse_lenient("Game 1 3 blue").is_err());
+        assert!(Game::parse_lenient("Game 1: 3 purple").is_err());
+    }
 }
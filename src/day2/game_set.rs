@@ -0,0 +1,73 @@
+use crate::day2::{parse_games, Game};
+use std::str::FromStr;
+
+/// All games from a puzzle input, in the order they appeared.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GameSet(pub Vec<Game>);
+
+impl FromStr for GameSet {
+    type Err = anyhow::Error;
+
+    /// Parses one game per line, see [`parse_games`].
+    fn from_str(input: &str) -> anyhow::Result<GameSet> {
+        Ok(GameSet(parse_games(input)?))
+    }
+}
+
+impl GameSet {
+    /// Number of games in the set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the games, in the order they appeared.
+    pub fn iter(&self) -> std::slice::Iter<'_, Game> {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for GameSet {
+    type Item = Game;
+    type IntoIter = std::vec::IntoIter<Game>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a GameSet {
+    type Item = &'a Game;
+    type IntoIter = std::slice::Iter<'a, Game>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::EXAMPLE_INPUT;
+
+    #[test]
+    fn test_game_set_collection_accessors() {
+        let games = EXAMPLE_INPUT.parse::<GameSet>().unwrap();
+        assert_eq!(games.len(), 5);
+        assert!(!games.is_empty());
+        assert!("".parse::<GameSet>().unwrap().is_empty());
+
+        let mut ids = vec![];
+        for game in &games {
+            ids.push(game.id);
+        }
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        assert_eq!(games.iter().map(|game| game.draws.len()).sum::<usize>(), 14);
+
+        let owned = games.into_iter().collect::<Vec<_>>();
+        assert_eq!(owned.len(), 5);
+    }
+}
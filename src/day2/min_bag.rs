@@ -0,0 +1,54 @@
+use crate::day2::Game;
+use std::fmt::Write;
+
+/// Header line of [`min_bag_csv`].
+const MIN_BAG_CSV_HEADER: &str = "id,red,green,blue,power";
+
+/// Formats the [`Game::minimum_bag_contents`] and [`Game::calculate_power`] of every game as
+/// CSV, with a header line and one `id,red,green,blue,power` row per game, in order.
+pub fn min_bag_csv(games: &[Game]) -> String {
+    let mut csv = format!("{}\n", MIN_BAG_CSV_HEADER);
+    for game in games {
+        let bag = game.minimum_bag_contents();
+        // Writing to a String never fails
+        writeln!(
+            csv,
+            "{},{},{},{},{}",
+            game.id,
+            bag.num_red,
+            bag.num_green,
+            bag.num_blue,
+            game.calculate_power()
+        )
+        .unwrap();
+    }
+    csv
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::{parse_games, EXAMPLE_INPUT};
+
+    #[test]
+    fn test_min_bag_csv_example_data() {
+        let games = parse_games(EXAMPLE_INPUT).unwrap();
+
+        assert_eq!(
+            min_bag_csv(&games),
+            "\
+id,red,green,blue,power
+1,4,2,6,48
+2,1,3,4,12
+3,20,13,6,1560
+4,14,3,15,630
+5,6,3,2,36
+"
+        );
+    }
+
+    #[test]
+    fn test_min_bag_csv_no_games() {
+        assert_eq!(min_bag_csv(&[]), "id,red,green,blue,power\n");
+    }
+}
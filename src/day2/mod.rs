@@ -1,71 +1,476 @@
 mod draw;
+mod explain;
 mod game;
+mod game_set;
+mod min_bag;
+mod report;
+mod stats;
 
-pub use draw::Draw;
-pub use game::Game;
+pub use draw::{canonical_draw_string, Color, Draw};
+pub use explain::explain_power;
+pub use game::{strip_comment, strip_comments, Game, ImpossibleReason};
+pub use game_set::GameSet;
+pub use min_bag::min_bag_csv;
+pub use report::GameReport;
+pub use stats::{summarize, GameStats};
 
-use anyhow::{Context, Result};
-use std::fs;
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, log_line_counts, read_input};
+#[cfg(feature = "cli")]
+use crate::parse::report_parse_errors;
+use crate::parse::{parse_lines, parse_lines_with};
+#[cfg(feature = "cli")]
+use crate::regex_profile;
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+#[cfg(any(feature = "cli", feature = "serde"))]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "cli")]
+use std::borrow::Cow;
+#[cfg(feature = "cli")]
 use std::path::PathBuf;
-use tracing::{debug, info, trace};
+#[cfg(feature = "cli")]
+use tracing::info;
+use tracing::{debug, trace};
 
 const PART1_MAX_RED_CUBES: u8 = 12;
 const PART1_MAX_GREEN_CUBES: u8 = 13;
 const PART1_MAX_BLUE_CUBES: u8 = 14;
 
+#[cfg(feature = "cli")]
 #[derive(clap::Args, Debug)]
 pub struct Args {
     /// Input data from the puzzle (list of games).
     /// Empty lines are ignored.
-    pub input: PathBuf,
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+
+    /// Print a report for every game: Whether it was possible with the part 1 bag
+    /// (and if not, why), and its power.
+    #[arg(long)]
+    pub report: bool,
+
+    /// Output format of the `--report`.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Accept games with extra whitespace (including tabs) around the separators and
+    /// empty draws, like `3 blue , 4 red ;`.
+    #[arg(long, visible_alias = "lenient")]
+    pub lenient_whitespace: bool,
+
+    /// Ignore trailing `#` comments, like `Game 1: 3 blue, 4 red  # seen twice`.
+    #[arg(long)]
+    pub allow_comments: bool,
+
+    /// Print statistics across all games: the most cubes of each color in a single draw,
+    /// the average number of cubes per draw, and the number of impossible games.
+    #[arg(long)]
+    pub stats: bool,
+
+    /// Print, for every game that was impossible with the part 1 bag, how many of its draws
+    /// have to be seen before that is certain.
+    #[arg(long)]
+    pub draws_until_impossible: bool,
+
+    /// Instead of solving, print the minimum bag contents and power of every game as CSV,
+    /// with the columns `id,red,green,blue,power`.
+    #[arg(long)]
+    pub min_bag_csv: bool,
+
+    /// Explain step by step how the minimum bag contents and power of the game
+    /// given by `--game-id` are computed.
+    #[arg(long, requires = "game_id")]
+    pub explain_tree: bool,
+
+    /// ID of the game to explain with `--explain-tree`.
+    #[arg(long)]
+    pub game_id: Option<u64>,
+
+    /// Format of the input file.
+    #[cfg(feature = "serde")]
+    #[arg(long, value_enum, default_value_t = InputFormat::Text, conflicts_with_all = ["lenient_whitespace", "collect_errors"])]
+    pub input_format: InputFormat,
+
+    /// Instead of solving, print all parsed games as a JSON array,
+    /// which can be read back with `--input-format json`.
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    pub dump_json: bool,
+
+    /// Instead of stopping at the first game that fails to parse, log every one of them
+    /// before failing.
+    #[arg(long)]
+    pub collect_errors: bool,
+
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+
+    /// Log (at `debug` level) the time spent matching the regexes that parse the input, and how
+    /// many matches there were. Purely diagnostic.
+    #[arg(long, hide = true)]
+    pub profile_regex: bool,
+
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+#[cfg(feature = "cli")]
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One human-readable line per game
+    Text,
+    /// A JSON array with one object per game
+    Json,
+}
+
+#[cfg(all(feature = "cli", feature = "serde"))]
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// The puzzle input, one game per line
+    Text,
+    /// A JSON array of games, as printed by `--dump-json`
+    Json,
 }
 
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of the IDs of all games that were possible with the part 1 bag
+    pub part1: u64,
+    /// Sum of the powers of all games
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
 pub fn run(args: Args) -> Result<()> {
-    let input: String = fs::read_to_string(&args.input)
-        .with_context(|| format!("While trying to read file {}", args.input.display()))?;
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+    log_line_counts(&input);
+    if args.profile_regex {
+        regex_profile::enable();
+    }
+    let parse_game: fn(&str) -> Result<Game> = if args.lenient_whitespace {
+        Game::parse_lenient
+    } else {
+        |line| line.parse()
+    };
+    if args.collect_errors {
+        report_parse_errors(&strip_comments_if(args.allow_comments, &input), parse_game)?;
+    }
+    let parse =
+        |input: &str| parse_games_with(&strip_comments_if(args.allow_comments, input), parse_game);
+    #[cfg(feature = "serde")]
+    let parse = |input: &str| match args.input_format {
+        InputFormat::Text => parse(input),
+        InputFormat::Json => parse_games_json(input),
+    };
 
-    let mut sum_of_possible_game_ids: u64 = 0;
-    let mut sum_of_powers: u64 = 0;
+    #[cfg(feature = "serde")]
+    if args.dump_json {
+        println!("{}", serde_json::to_string_pretty(&parse(&input)?)?);
+        return Ok(());
+    }
+
+    if args.min_bag_csv {
+        print!("{}", min_bag_csv(&parse(&input)?));
+        return Ok(());
+    }
+
+    if args.report {
+        let reports = parse(&input)?
+            .iter()
+            .map(|game| {
+                GameReport::new(
+                    game,
+                    PART1_MAX_RED_CUBES,
+                    PART1_MAX_GREEN_CUBES,
+                    PART1_MAX_BLUE_CUBES,
+                )
+            })
+            .collect::<Vec<_>>();
 
-    for (line_idx, line) in input.lines().enumerate() {
-        if line.is_empty() {
-            continue;
+        match args.format {
+            Format::Text => {
+                for report in &reports {
+                    println!("{}", report);
+                }
+            }
+            Format::Json => {
+                let json = reports.iter().map(GameReport::to_json).collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
         }
+    }
+
+    if args.stats {
+        info!("Statistics: {}", summarize(&parse(&input)?));
+    }
+
+    if args.draws_until_impossible {
+        let bag = Draw {
+            num_red: PART1_MAX_RED_CUBES,
+            num_green: PART1_MAX_GREEN_CUBES,
+            num_blue: PART1_MAX_BLUE_CUBES,
+        };
+        for game in parse(&input)? {
+            if let Some(draws) = game.draws_until_impossible(&bag) {
+                info!(
+                    "Game {}: impossible after {} of {} draws",
+                    game.id,
+                    draws,
+                    game.draws.len()
+                );
+            }
+        }
+    }
+
+    if args.explain_tree {
+        // `requires = "game_id"` makes sure the ID is present
+        let game_id = args.game_id.context("--explain-tree requires --game-id")?;
+        let games = parse(&input)?;
+        let game = games
+            .iter()
+            .find(|game| game.id == game_id)
+            .with_context(|| format!("There is no game with ID {}", game_id))?;
+        print!("{}", explain_power(game));
+    }
+
+    let answers = solve_repeatedly(&input, args.repeat, |input| Ok(solve_games(&parse(input)?)))?;
+    if args.profile_regex {
+        regex_profile::log_profile();
+    }
+    info!("(Part 1) Sum of all possible games IDs: {}", answers.part1);
+    info!("(Part 2) Sum of all powers: {}", answers.part2);
+
+    Ok(())
+}
+
+/// Strips the comments from the whole input (if they are allowed) before it is parsed, so that
+/// lines with only a comment are skipped like empty lines.
+#[cfg(feature = "cli")]
+fn strip_comments_if(allow_comments: bool, input: &str) -> Cow<'_, str> {
+    if allow_comments {
+        Cow::Owned(strip_comments(input))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day2::{solve, Answers};
+/// let answers = solve("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 1, part2: 48 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    Ok(solve_games(&parse_games(input)?))
+}
 
-        let game = line
-            .parse::<Game>()
-            .with_context(|| format!("While trying to parse line {} (`{}`)", line_idx + 1, line))?;
+fn solve_games(games: &[Game]) -> Answers {
+    let mut sum_of_powers: u64 = 0;
+
+    for game in games {
+        let power = game.calculate_power();
+
+        debug!("Game {}: power = {}", game.id, power);
+        trace!("(was parsed as {:?})", game);
+
+        sum_of_powers += power as u64;
+    }
+
+    // Each game is checked once, while summing the IDs
+    let sum_of_possible_ids = possible_games(
+        games,
+        PART1_MAX_RED_CUBES,
+        PART1_MAX_GREEN_CUBES,
+        PART1_MAX_BLUE_CUBES,
+    )
+    .inspect(|game| debug!("Game {}: possible", game.id))
+    .map(|game| game.id)
+    .sum();
+
+    Answers {
+        part1: sum_of_possible_ids,
+        part2: sum_of_powers,
+    }
+}
+
+/// The games that were possible with a bag of the given number of cubes, in their original order.
+pub fn possible_games(
+    games: &[Game],
+    max_red: u8,
+    max_green: u8,
+    max_blue: u8,
+) -> impl Iterator<Item = &Game> {
+    games
+        .iter()
+        .filter(move |game| game.was_possible(max_red, max_green, max_blue))
+}
+
+/// Sum of the IDs of the [`possible_games`], which is the answer to part 1 with the part 1 bag.
+pub fn sum_possible_ids(games: &[Game], max_red: u8, max_green: u8, max_blue: u8) -> u64 {
+    possible_games(games, max_red, max_green, max_blue)
+        .map(|game| game.id)
+        .sum()
+}
+
+/// Parses every non-empty line of the input as a [`Game`].
+pub fn parse_games(input: &str) -> Result<Vec<Game>> {
+    parse_lines(input)
+}
+
+/// Like [`parse_games`], but tolerates extra whitespace and empty draws,
+/// see [`Game::parse_lenient`].
+pub fn parse_games_lenient(input: &str) -> Result<Vec<Game>> {
+    parse_games_with(input, Game::parse_lenient)
+}
+
+/// Parses a JSON array of games, in the format that [`Game`] is serialized to, e.g.
+/// `[{"id": 1, "draws": [{"num_red": 4, "num_green": 0, "num_blue": 3}]}]`.
+#[cfg(feature = "serde")]
+pub fn parse_games_json(input: &str) -> Result<Vec<Game>> {
+    serde_json::from_str(input).context("While trying to parse the games as JSON")
+}
+
+fn parse_games_with<F>(input: &str, parse_game: F) -> Result<Vec<Game>>
+where
+    F: Fn(&str) -> Result<Game>,
+{
+    parse_lines_with(input, parse_game)
+}
+
+/// The five games from the puzzle description, shared by the tests of every day 2 module.
+#[cfg(test)]
+pub(crate) const EXAMPLE_INPUT: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
 
-        let game_was_possible = game.was_possible(
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 8,
+                part2: 2286
+            }
+        );
+    }
+
+    #[test]
+    fn test_possible_games_example_data() {
+        let games = parse_games(EXAMPLE_INPUT).unwrap();
+        let possible_ids = possible_games(
+            &games,
+            PART1_MAX_RED_CUBES,
+            PART1_MAX_GREEN_CUBES,
+            PART1_MAX_BLUE_CUBES,
+        )
+        .map(|game| game.id)
+        .collect::<std::collections::HashSet<_>>();
+        assert_eq!(possible_ids, [1, 2, 5].into_iter().collect());
+
+        let sum = sum_possible_ids(
+            &games,
             PART1_MAX_RED_CUBES,
             PART1_MAX_GREEN_CUBES,
             PART1_MAX_BLUE_CUBES,
         );
-        let power = game.calculate_power();
+        assert_eq!(sum, 8);
+        // Nothing is possible with an empty bag
+        assert_eq!(sum_possible_ids(&games, 0, 0, 0), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parse_games_json_round_trip() {
+        let games = parse_games(EXAMPLE_INPUT).unwrap();
+        let json = serde_json::to_string_pretty(&games).unwrap();
+
+        let parsed = parse_games_json(&json).unwrap();
+        assert_eq!(parsed, games);
+        assert_eq!(solve_games(&parsed), solve(EXAMPLE_INPUT).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parse_games_json_invalid() {
+        let err = parse_games_json(r#"[{"id": 1}]"#).unwrap_err();
+        assert_eq!(err.to_string(), "While trying to parse the games as JSON");
+    }
 
-        debug!(
-            "{}: {}, power = {}",
-            line,
-            if game_was_possible {
-                "possible"
-            } else {
-                "impossible"
-            },
-            power
+    #[test]
+    fn test_parse_games_with_comment_only_lines() {
+        let input = "# Games from the puzzle\nGame 1: 3 blue, 4 red  # seen twice\n\n# end\n";
+        let games = parse_games_with(&strip_comments(input), str::parse::<Game>).unwrap();
+        assert_eq!(
+            games,
+            vec!["Game 1: 3 blue, 4 red".parse::<Game>().unwrap()]
         );
-        trace!("(was parsed as {:?})", game);
 
-        if game_was_possible {
-            sum_of_possible_game_ids += game.id;
-        }
-        sum_of_powers += power as u64;
+        // Line numbers in errors still refer to the original input
+        let err = parse_games_with(&strip_comments("# note\nGame 2: 1 purple # x\n"), |line| {
+            line.parse::<Game>()
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse line 2: `Game 2: 1 purple`"
+        );
     }
 
-    info!(
-        "(Part 1) Sum of all possible games IDs: {}",
-        sum_of_possible_game_ids
-    );
-    info!("(Part 2) Sum of all powers: {}", sum_of_powers);
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_run_allow_comments_with_comment_only_line() {
+        use crate::{Args, Day, DayCommand};
+        use clap::Parser;
 
-    Ok(())
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day2.txt");
+        std::fs::write(&path, format!("# The example\n{EXAMPLE_INPUT}# end\n")).unwrap();
+
+        let args = Args::try_parse_from([
+            "advent-of-code-2023".as_ref(),
+            "day2".as_ref(),
+            "--allow-comments".as_ref(),
+            path.as_os_str(),
+        ])
+        .unwrap();
+        let Day::Solve(DayCommand::Day2(args)) = args.day else {
+            panic!("Expected day 2 arguments");
+        };
+        run(args).unwrap();
+    }
+
+    #[test]
+    fn test_solve_crlf_line_endings() {
+        let crlf_input = EXAMPLE_INPUT.replace('\n', "\r\n");
+        assert_eq!(solve(&crlf_input).unwrap(), solve(EXAMPLE_INPUT).unwrap());
+    }
 }
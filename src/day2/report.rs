@@ -0,0 +1,111 @@
+use crate::day2::{Game, ImpossibleReason};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Summary of a single game for the `--report` output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameReport {
+    pub id: u64,
+    pub possible: bool,
+    pub power: u32,
+    /// Why the game was impossible, `None` if it was possible
+    pub reason: Option<ImpossibleReason>,
+}
+
+impl GameReport {
+    /// Evaluates the game against a bag with the given number of red, green and blue cubes.
+    pub fn new(game: &Game, max_red: u8, max_green: u8, max_blue: u8) -> GameReport {
+        let reason = game.impossible_reason(max_red, max_green, max_blue);
+        GameReport {
+            id: game.id,
+            possible: reason.is_none(),
+            power: game.calculate_power(),
+            reason,
+        }
+    }
+
+    /// Converts the report to a JSON object like
+    /// `{"id": 3, "possible": false, "power": 1560, "reason": {"draw": 1, "color": "red", "drawn": 20, "available": 12}}`.
+    /// `draw` is 1-based. The `reason` key is left out for possible games.
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({
+            "id": self.id,
+            "possible": self.possible,
+            "power": self.power,
+        });
+        if let Some(reason) = &self.reason {
+            value["reason"] = json!({
+                "draw": reason.draw_idx + 1,
+                "color": reason.color.name(),
+                "drawn": reason.drawn,
+                "available": reason.available,
+            });
+        }
+        value
+    }
+}
+
+impl fmt::Display for GameReport {
+    /// Formats the report like `Game 3: impossible (draw 1 has 20 red, bag has 12), power = 1560`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}: ", self.id)?;
+        match &self.reason {
+            None => write!(f, "possible")?,
+            Some(reason) => write!(
+                f,
+                "impossible (draw {} has {} {}, bag has {})",
+                reason.draw_idx + 1,
+                reason.drawn,
+                reason.color.name(),
+                reason.available
+            )?,
+        }
+        write!(f, ", power = {}", self.power)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_report_impossible_game() {
+        let game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
+            .parse::<Game>()
+            .unwrap();
+        let report = GameReport::new(&game, 12, 13, 14);
+
+        assert_eq!(
+            report.to_json(),
+            json!({
+                "id": 3,
+                "possible": false,
+                "power": 1560,
+                "reason": {
+                    "draw": 1,
+                    "color": "red",
+                    "drawn": 20,
+                    "available": 12,
+                },
+            })
+        );
+        assert_eq!(
+            report.to_string(),
+            "Game 3: impossible (draw 1 has 20 red, bag has 12), power = 1560"
+        );
+    }
+
+    #[test]
+    fn test_report_possible_game() {
+        let game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+            .parse::<Game>()
+            .unwrap();
+        let report = GameReport::new(&game, 12, 13, 14);
+
+        let json = report.to_json();
+        assert_eq!(json["possible"], json!(true));
+        assert_eq!(json["power"], json!(48));
+        assert!(json.get("reason").is_none());
+        assert_eq!(report.to_string(), "Game 1: possible, power = 48");
+    }
+}
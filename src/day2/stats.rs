@@ -0,0 +1,98 @@
+use crate::day2::{Game, PART1_MAX_BLUE_CUBES, PART1_MAX_GREEN_CUBES, PART1_MAX_RED_CUBES};
+use std::fmt;
+
+/// Aggregate statistics across all games, for the `--stats` output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameStats {
+    /// Most red cubes seen in a single draw
+    pub max_red: u8,
+    /// Most green cubes seen in a single draw
+    pub max_green: u8,
+    /// Most blue cubes seen in a single draw
+    pub max_blue: u8,
+    /// Average number of cubes per draw, `None` if there were no draws
+    pub average_draw_size: Option<f64>,
+    /// Number of games that were impossible with the part 1 bag
+    pub impossible_games: usize,
+}
+
+/// Computes the [`GameStats`] of the given games.
+pub fn summarize(games: &[Game]) -> GameStats {
+    let mut stats = GameStats {
+        max_red: 0,
+        max_green: 0,
+        max_blue: 0,
+        average_draw_size: None,
+        impossible_games: 0,
+    };
+    let mut num_draws = 0u64;
+    let mut num_cubes = 0u64;
+
+    for game in games {
+        let minimum_bag_contents = game.minimum_bag_contents();
+        stats.max_red = stats.max_red.max(minimum_bag_contents.num_red);
+        stats.max_green = stats.max_green.max(minimum_bag_contents.num_green);
+        stats.max_blue = stats.max_blue.max(minimum_bag_contents.num_blue);
+
+        num_draws += game.draws.len() as u64;
+        num_cubes += game.total_cubes() as u64;
+
+        if !game.was_possible(
+            PART1_MAX_RED_CUBES,
+            PART1_MAX_GREEN_CUBES,
+            PART1_MAX_BLUE_CUBES,
+        ) {
+            stats.impossible_games += 1;
+        }
+    }
+
+    if num_draws > 0 {
+        stats.average_draw_size = Some(num_cubes as f64 / num_draws as f64);
+    }
+    stats
+}
+
+impl fmt::Display for GameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "most cubes in a draw: {} red, {} green, {} blue; ",
+            self.max_red, self.max_green, self.max_blue
+        )?;
+        match self.average_draw_size {
+            Some(average) => write!(f, "average draw size: {:.2} cubes; ", average)?,
+            None => write!(f, "average draw size: - (no draws); ")?,
+        }
+        write!(f, "impossible games: {}", self.impossible_games)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::{parse_games, EXAMPLE_INPUT};
+
+    #[test]
+    fn test_summarize_example_data() {
+        let stats = summarize(&parse_games(EXAMPLE_INPUT).unwrap());
+        assert_eq!(stats.max_red, 20);
+        assert_eq!(stats.max_green, 13);
+        assert_eq!(stats.max_blue, 15);
+        // Games 3 and 4
+        assert_eq!(stats.impossible_games, 2);
+        // 159 cubes in 14 draws
+        assert_eq!(stats.average_draw_size, Some(159.0 / 14.0));
+        assert_eq!(
+            stats.to_string(),
+            "most cubes in a draw: 20 red, 13 green, 15 blue; average draw size: 11.36 cubes; impossible games: 2"
+        );
+    }
+
+    #[test]
+    fn test_summarize_no_games() {
+        let stats = summarize(&[]);
+        assert_eq!(stats.max_red, 0);
+        assert_eq!(stats.average_draw_size, None);
+        assert_eq!(stats.impossible_games, 0);
+    }
+}
@@ -0,0 +1,513 @@
+use crate::answer::{Answer, DayResult};
+use crate::day8::lcm;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+use crate::parse::parse_lines_with;
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(feature = "cli")]
+use tracing::info;
+use tracing::{debug, warn};
+
+/// How often the button is pushed in part 1.
+pub const BUTTON_PRESSES: u64 = 1000;
+/// The module that receives the first pulse of every button press.
+pub const BROADCASTER: &str = "broadcaster";
+/// The module that should receive a single low pulse (part 2).
+pub const RX: &str = "rx";
+/// Part 2 gives up after this many button presses.
+pub const MAX_PRESSES: u64 = 1_000_000;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (one module per line, like `%a -> inv, con`).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Number of low pulses times number of high pulses after 1000 button presses
+    pub part1: u64,
+    /// Fewest button presses until a single low pulse is sent to `rx`, or `None` if there is
+    /// no `rx` module (like in the examples) or it is not wired up like in the puzzle inputs
+    pub part2: Option<u64>,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        let part2 = answers.part2.map_or(Answer::from("-"), Answer::Number);
+        DayResult::new(answers.part1, part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Low pulses times high pulses: {}", answers.part1);
+    match answers.part2 {
+        Some(presses) => info!("(Part 2) Button presses until {RX} gets a low pulse: {presses}"),
+        None => warn!("(Part 2) Skipped, there is no {RX} module it can be solved for"),
+    }
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day20::{solve, Answers};
+/// let answers = solve("broadcaster -> a\n%a -> rx\n").unwrap();
+/// // Every press sends two low pulses, then `a` alternates between a high and a low pulse
+/// assert_eq!(answers, Answers { part1: 2500 * 500, part2: Some(2) });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let network = input.parse::<Network>()?;
+
+    let (low, high) = pulse_counts(&network, BUTTON_PRESSES);
+    Ok(Answers {
+        part1: low * high,
+        part2: fewest_presses_to_rx(&network)?,
+    })
+}
+
+/// What a module does with the pulses it receives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+    /// Sends every pulse on to all of its outputs
+    Broadcaster,
+    /// `%`: Ignores high pulses, and flips between on and off on low pulses, sending a high
+    /// pulse when turned on and a low pulse when turned off
+    FlipFlop,
+    /// `&`: Remembers the last pulse from each input, and sends a low pulse if all of them were
+    /// high, otherwise a high pulse
+    Conjunction,
+    /// A module that is only mentioned as an output, like `rx`. It ignores every pulse.
+    Untyped,
+}
+
+/// The modules and how they are wired up, in their initial state (every flip-flop off, every
+/// conjunction remembering low pulses).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    /// Name of every module, in order of appearance (as a source, then as an output)
+    pub names: Vec<String>,
+    pub kinds: Vec<ModuleKind>,
+    /// Indices of the modules each module sends its pulses to
+    pub outputs: Vec<Vec<usize>>,
+    /// Indices of the modules that send pulses to each module
+    pub inputs: Vec<Vec<usize>>,
+    /// Index of each module by name
+    index: HashMap<String, usize>,
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    /// Parses one module per line, like `broadcaster -> a, b`, `%a -> b` or `&inv -> a`.
+    /// Empty lines are ignored. There must be exactly one `broadcaster`.
+    fn from_str(input: &str) -> Result<Network> {
+        let definitions = parse_lines_with(input, parse_module)?;
+
+        let mut network = Network {
+            names: vec![],
+            kinds: vec![],
+            outputs: vec![],
+            inputs: vec![],
+            index: HashMap::new(),
+        };
+        for &(kind, name, _) in &definitions {
+            ensure!(
+                !network.index.contains_key(name),
+                "The module `{}` is defined more than once",
+                name
+            );
+            network.add_module(name, kind);
+        }
+        ensure!(
+            network.module(BROADCASTER).map(|idx| network.kinds[idx])
+                == Some(ModuleKind::Broadcaster),
+            "There is no `{}` module",
+            BROADCASTER
+        );
+
+        for (_, name, outputs) in definitions {
+            let from = network.index[name];
+            for output in outputs {
+                let to = match network.index.get(output) {
+                    Some(&to) => to,
+                    None => network.add_module(output, ModuleKind::Untyped),
+                };
+                network.outputs[from].push(to);
+                network.inputs[to].push(from);
+            }
+        }
+
+        Ok(network)
+    }
+}
+
+/// Parses a single line into the kind of module, its name and the names of its outputs.
+fn parse_module(line: &str) -> Result<(ModuleKind, &str, Vec<&str>)> {
+    let (module, outputs) = line
+        .split_once(" -> ")
+        .context("Expected a module and its outputs, like `%a -> b, c`")?;
+
+    let (kind, name) = if let Some(name) = module.strip_prefix('%') {
+        (ModuleKind::FlipFlop, name)
+    } else if let Some(name) = module.strip_prefix('&') {
+        (ModuleKind::Conjunction, name)
+    } else if module == BROADCASTER {
+        (ModuleKind::Broadcaster, module)
+    } else {
+        bail!(
+            "Invalid module `{}`, expected `{}`, or a name prefixed with `%` or `&`",
+            module,
+            BROADCASTER
+        );
+    };
+    ensure!(
+        is_valid_name(name),
+        "Invalid module name `{}`, expected lowercase letters",
+        name
+    );
+
+    let outputs = outputs.split(", ").map(str::trim).collect::<Vec<_>>();
+    if let Some(output) = outputs.iter().find(|output| !is_valid_name(output)) {
+        bail!(
+            "Invalid output `{}`, expected a module name of lowercase letters",
+            output
+        );
+    }
+
+    Ok((kind, name, outputs))
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase())
+}
+
+impl Network {
+    /// Adds a module without any connections, returning its index.
+    fn add_module(&mut self, name: &str, kind: ModuleKind) -> usize {
+        let idx = self.names.len();
+        self.names.push(name.to_owned());
+        self.kinds.push(kind);
+        self.outputs.push(vec![]);
+        self.inputs.push(vec![]);
+        self.index.insert(name.to_owned(), idx);
+        idx
+    }
+
+    /// Index of the module with the given name.
+    pub fn module(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+}
+
+/// A pulse on its way from one module to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pulse {
+    pub from: usize,
+    pub to: usize,
+    pub high: bool,
+}
+
+/// The state of every module while pulses are sent through the [`Network`].
+#[derive(Debug, Clone)]
+pub struct Simulation<'a> {
+    network: &'a Network,
+    /// Whether each flip-flop is on
+    on: Vec<bool>,
+    /// For each conjunction, the last pulse (`true` for high) from each of its inputs, in the
+    /// order of [`Network::inputs`]
+    memory: Vec<Vec<bool>>,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(network: &'a Network) -> Simulation<'a> {
+        Simulation {
+            network,
+            on: vec![false; network.names.len()],
+            memory: network
+                .inputs
+                .iter()
+                .map(|inputs| vec![false; inputs.len()])
+                .collect(),
+        }
+    }
+
+    /// Pushes the button once, which sends a low pulse to the broadcaster, and processes pulses
+    /// in the order they were sent until there are none left. `on_pulse` is called for every
+    /// pulse, including the one from the button (which comes from the broadcaster to itself).
+    pub fn press_button(&mut self, mut on_pulse: impl FnMut(Pulse)) {
+        let broadcaster = self.network.index[BROADCASTER];
+        let mut queue = VecDeque::from([Pulse {
+            from: broadcaster,
+            to: broadcaster,
+            high: false,
+        }]);
+
+        while let Some(pulse) = queue.pop_front() {
+            on_pulse(pulse);
+
+            let Some(high) = self.receive(pulse) else {
+                continue;
+            };
+            queue.extend(self.network.outputs[pulse.to].iter().map(|&to| Pulse {
+                from: pulse.to,
+                to,
+                high,
+            }));
+        }
+    }
+
+    /// Updates the receiving module's state, and returns the pulse it sends to all of its
+    /// outputs in response (`true` for high), if any.
+    fn receive(&mut self, pulse: Pulse) -> Option<bool> {
+        match self.network.kinds[pulse.to] {
+            ModuleKind::Broadcaster => Some(pulse.high),
+            ModuleKind::FlipFlop if pulse.high => None,
+            ModuleKind::FlipFlop => {
+                let on = &mut self.on[pulse.to];
+                *on = !*on;
+                Some(*on)
+            }
+            ModuleKind::Conjunction => {
+                let inputs = &self.network.inputs[pulse.to];
+                let memory = &mut self.memory[pulse.to];
+                for (input, remembered) in inputs.iter().zip(memory.iter_mut()) {
+                    if *input == pulse.from {
+                        *remembered = pulse.high;
+                    }
+                }
+                Some(!memory.iter().all(|&high| high))
+            }
+            ModuleKind::Untyped => None,
+        }
+    }
+}
+
+/// Counts the low and high pulses sent while pushing the button `presses` times, starting from
+/// the initial state. The pulse from the button itself counts as a low pulse.
+pub fn pulse_counts(network: &Network, presses: u64) -> (u64, u64) {
+    let mut simulation = Simulation::new(network);
+    let (mut low, mut high) = (0, 0);
+    for _ in 0..presses {
+        simulation.press_button(|pulse| {
+            if pulse.high {
+                high += 1;
+            } else {
+                low += 1;
+            }
+        });
+    }
+    (low, high)
+}
+
+/// Finds the fewest button presses until a single low pulse is sent to `rx`. `None` if there
+/// is no `rx` module, or if it is wired up in a way this doesn't support (which is logged).
+///
+/// Simulating until then takes far too long. Instead, this relies on the structure of the
+/// puzzle inputs: `rx` is fed by a single conjunction, which sends a low pulse once all of its
+/// inputs sent it a high pulse during the same press. Each of those inputs sends a high pulse
+/// periodically, first after exactly its period, so they all line up after the least common
+/// multiple of the presses until each input's first high pulse.
+pub fn fewest_presses_to_rx(network: &Network) -> Result<Option<u64>> {
+    let Some(rx) = network.module(RX) else {
+        return Ok(None);
+    };
+
+    if network.inputs[rx]
+        .iter()
+        .any(|&input| network.kinds[input] == ModuleKind::Broadcaster)
+    {
+        // The broadcaster passes on the low pulse of the very first press
+        return Ok(Some(1));
+    }
+    let feeder = match network.inputs[rx][..] {
+        [feeder] => feeder,
+        _ => {
+            warn!("Expected `{}` to have exactly one input", RX);
+            return Ok(None);
+        }
+    };
+    match network.kinds[feeder] {
+        ModuleKind::Conjunction => {}
+        // A flip-flop sends a low pulse whenever it's turned off, so the first time it does
+        ModuleKind::FlipFlop => {
+            return first_press_with(network, |pulse| pulse.from == feeder && !pulse.high).map(Some)
+        }
+        _ => {
+            warn!(
+                "Expected `{}` to be fed by a conjunction or a flip-flop",
+                RX
+            );
+            return Ok(None);
+        }
+    }
+
+    let mut presses = 1;
+    for &input in &network.inputs[feeder] {
+        let first_high = first_press_with(network, |pulse| {
+            pulse.from == input && pulse.to == feeder && pulse.high
+        })?;
+        debug!(
+            "`{}` first sends a high pulse to `{}` on press {}",
+            network.names[input], network.names[feeder], first_high
+        );
+        presses = lcm(presses, first_high);
+    }
+    Ok(Some(presses))
+}
+
+/// Presses the button (starting from the initial state) until `condition` holds for a pulse,
+/// and returns how many presses that took.
+fn first_press_with(network: &Network, mut condition: impl FnMut(&Pulse) -> bool) -> Result<u64> {
+    let mut simulation = Simulation::new(network);
+    for presses in 1..=MAX_PRESSES {
+        let mut found = false;
+        simulation.press_button(|pulse| found |= condition(&pulse));
+        if found {
+            return Ok(presses);
+        }
+    }
+    bail!(
+        "Gave up waiting for the pulse after {} button presses",
+        MAX_PRESSES
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a
+";
+
+    const SECOND_EXAMPLE_INPUT: &str = "\
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output
+";
+
+    #[test]
+    fn test_solve_example_data() {
+        assert_eq!(
+            solve(EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 32000000,
+                part2: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_solve_second_example_data() {
+        assert_eq!(
+            solve(SECOND_EXAMPLE_INPUT).unwrap(),
+            Answers {
+                part1: 11687500,
+                part2: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_pulse_counts_single_press() {
+        let network = EXAMPLE_INPUT.parse::<Network>().unwrap();
+        assert_eq!(pulse_counts(&network, 1), (8, 4));
+
+        let network = SECOND_EXAMPLE_INPUT.parse::<Network>().unwrap();
+        assert_eq!(pulse_counts(&network, 1), (4, 4));
+    }
+
+    #[test]
+    fn test_fewest_presses_to_rx() {
+        // `bb` sends a high pulse to `fd` every 4th press, `cc` every 2nd press. `fd` sends a
+        // low pulse to `rx` when both do so in the same press.
+        let input = "\
+broadcaster -> a, c
+%a -> b
+%b -> bb
+&bb -> fd
+%c -> cc
+&cc -> fd
+&fd -> rx
+";
+        let network = input.parse::<Network>().unwrap();
+        assert_eq!(fewest_presses_to_rx(&network).unwrap(), Some(4));
+
+        // Same as simulating until it happens
+        let rx = network.module(RX).unwrap();
+        let simulated = first_press_with(&network, |pulse| pulse.to == rx && !pulse.high);
+        assert_eq!(simulated.unwrap(), 4);
+
+        let network = "broadcaster -> a\n%a -> rx\n".parse::<Network>().unwrap();
+        assert_eq!(fewest_presses_to_rx(&network).unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_solve_with_unsupported_rx() {
+        // `rx` has two inputs, so part 2 is skipped, but part 1 is still solved: Every two
+        // presses send 8 low pulses and 2 high pulses
+        let answers = solve("broadcaster -> a, b\n%a -> rx\n%b -> rx\n").unwrap();
+        assert_eq!(
+            answers,
+            Answers {
+                part1: 4000 * 1000,
+                part2: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_network() {
+        let network = SECOND_EXAMPLE_INPUT.parse::<Network>().unwrap();
+        let output = network.module("output").unwrap();
+        assert_eq!(network.kinds[output], ModuleKind::Untyped);
+        let con = network.module("con").unwrap();
+        assert_eq!(network.inputs[con].len(), 2);
+
+        let err = "%a -> b\n".parse::<Network>().unwrap_err();
+        assert_eq!(err.to_string(), "There is no `broadcaster` module");
+        let err = "broadcaster -> a\n%a -> b\n&a -> b"
+            .parse::<Network>()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "The module `a` is defined more than once");
+        let err = "broadcaster -> a\n$a -> b".parse::<Network>().unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 2: `$a -> b`");
+        assert!("broadcaster -> A".parse::<Network>().is_err());
+        assert!("broadcaster a".parse::<Network>().is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::answer::{Answer, DayResult};
+use crate::grid::Grid;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+use std::collections::{HashSet, VecDeque};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use tracing::{info, warn};
+
+/// Number of steps the elf takes on the finite map (part 1).
+pub const PART1_STEPS: u64 = 64;
+/// Number of steps the elf takes on the infinitely repeating map (part 2).
+pub const PART2_STEPS: u64 = 26501365;
+
+/// The starting position of the elf, which is also a garden plot.
+pub const START: char = 'S';
+pub const GARDEN_PLOT: char = '.';
+pub const ROCK: char = '#';
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (the map of garden plots `.`, rocks `#` and the start `S`).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Number of garden plots reachable in exactly 64 steps
+    pub part1: u64,
+    /// Number of garden plots reachable in exactly 26501365 steps on the infinite map, or `None`
+    /// if the map is not laid out like the real puzzle inputs (see [`fit_reachable_in_infinite`])
+    pub part2: Option<u64>,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        let part2 = answers.part2.map_or(Answer::from("-"), Answer::Number);
+        DayResult::new(answers.part1, part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!(
+        "(Part 1) Garden plots reachable in {PART1_STEPS} steps: {}",
+        answers.part1
+    );
+    match answers.part2 {
+        Some(plots) => {
+            info!("(Part 2) Garden plots reachable in {PART2_STEPS} steps on the infinite map: {plots}")
+        }
+        None => warn!("(Part 2) Skipped, the map is not square with a clear row and column through the start in its center"),
+    }
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day21::{solve, Answers};
+/// let answers = solve("...\n.S.\n...\n").unwrap();
+/// // The center and the four corners are an even number of steps away
+/// assert_eq!(answers, Answers { part1: 5, part2: Some(26501366 * 26501366) });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let grid = parse_garden(input)?;
+
+    Ok(Answers {
+        part1: reachable_in(&grid, PART1_STEPS),
+        part2: fit_reachable_in_infinite(&grid, PART2_STEPS)?,
+    })
+}
+
+/// Parses the map of the garden. All lines must be equally long and may only contain garden
+/// plots `.`, rocks `#` and exactly one start `S`.
+pub fn parse_garden(input: &str) -> Result<Grid<char>> {
+    let grid = input.parse::<Grid<char>>()?;
+
+    for (row, col, &cell) in grid.iter() {
+        ensure!(
+            matches!(cell, GARDEN_PLOT | ROCK | START),
+            "Invalid character `{}` on line {}, column {}, expected `{}`, `{}` or `{}`",
+            cell,
+            row + 1,
+            col + 1,
+            GARDEN_PLOT,
+            ROCK,
+            START
+        );
+    }
+    match grid.iter().filter(|&(_, _, &cell)| cell == START).count() {
+        1 => Ok(grid),
+        0 => bail!("The map has no start `{}`", START),
+        starts => bail!("The map has {} starts `{}`, expected one", starts, START),
+    }
+}
+
+/// Counts the garden plots that can be reached from the start `S` in exactly `steps` steps,
+/// without leaving the map. Returns 0 if there is no start.
+///
+/// Stepping back and forth, a plot `d` steps away can be reached in exactly `steps` steps if
+/// `d <= steps` and `d` has the same parity as `steps`.
+pub fn reachable_in(grid: &Grid<char>, steps: u64) -> u64 {
+    count_reachable(grid, steps, false)
+}
+
+/// Like [`reachable_in`], but on a map that repeats infinitely in every direction.
+///
+/// This is a breadth-first search over every plot within `steps` steps, so it is only feasible
+/// for a few hundred steps. See [`fit_reachable_in_infinite`] for more.
+pub fn reachable_in_infinite(grid: &Grid<char>, steps: u64) -> u64 {
+    count_reachable(grid, steps, true)
+}
+
+/// Like [`reachable_in_infinite`], but for huge numbers of steps.
+///
+/// On the real puzzle inputs, the map is square, the start is in its center and its row and
+/// column contain no rocks. Then the number of reachable plots grows quadratically with every
+/// further map width that is walked, so it is sampled at `n`, `n + width` and `n + 2 * width`
+/// steps (where `n = steps % width`) and a quadratic is fitted through these three values.
+///
+/// Returns `None` if the map is not laid out like that.
+pub fn fit_reachable_in_infinite(grid: &Grid<char>, steps: u64) -> Result<Option<u64>> {
+    let size = grid.width();
+    let center = size / 2;
+    let is_fittable = size == grid.height()
+        && size % 2 == 1
+        && grid.get(center as isize, center as isize) == Some(&START)
+        && (0..size).all(|idx| {
+            grid.get(center as isize, idx as isize) != Some(&ROCK)
+                && grid.get(idx as isize, center as isize) != Some(&ROCK)
+        });
+    if !is_fittable {
+        return Ok(None);
+    }
+
+    let size = size as u64;
+    let remainder = steps % size;
+    if steps <= remainder + 2 * size {
+        return Ok(Some(reachable_in_infinite(grid, steps)));
+    }
+
+    let samples =
+        [0, 1, 2].map(|widths| i128::from(reachable_in_infinite(grid, remainder + widths * size)));
+    let first_difference = samples[1] - samples[0];
+    let second_difference = samples[2] - 2 * samples[1] + samples[0];
+
+    let widths = i128::from((steps - remainder) / size);
+    let reachable =
+        samples[0] + widths * first_difference + widths * (widths - 1) / 2 * second_difference;
+    u64::try_from(reachable)
+        .map(Some)
+        .with_context(|| format!("{} reachable garden plots do not fit into a u64", reachable))
+}
+
+fn count_reachable(grid: &Grid<char>, steps: u64, infinite: bool) -> u64 {
+    let Some((start_row, start_col, _)) = grid.iter().find(|&(_, _, &cell)| cell == START) else {
+        return 0;
+    };
+    let (height, width) = (grid.height() as isize, grid.width() as isize);
+    let is_garden_plot = |row: isize, col: isize| {
+        let (row, col) = if infinite {
+            (row.rem_euclid(height), col.rem_euclid(width))
+        } else {
+            (row, col)
+        };
+        grid.get(row, col).is_some_and(|&cell| cell != ROCK)
+    };
+
+    let start = (start_row as isize, start_col as isize);
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut reachable = 0;
+    while let Some(((row, col), distance)) = queue.pop_front() {
+        if distance % 2 == steps % 2 {
+            reachable += 1;
+        }
+        if distance == steps {
+            continue;
+        }
+
+        for (row_offset, col_offset) in [(-1, 0), (0, -1), (0, 1), (1, 0)] {
+            let neighbor = (row + row_offset, col + col_offset);
+            if is_garden_plot(neighbor.0, neighbor.1) && visited.insert(neighbor) {
+                queue.push_back((neighbor, distance + 1));
+            }
+        }
+    }
+    reachable
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........
+";
+
+    #[test]
+    fn test_reachable_in_example_data() {
+        let grid = parse_garden(EXAMPLE_INPUT).unwrap();
+        assert_eq!(reachable_in(&grid, 0), 1);
+        assert_eq!(reachable_in(&grid, 1), 2);
+        assert_eq!(reachable_in(&grid, 2), 4);
+        assert_eq!(reachable_in(&grid, 3), 6);
+        assert_eq!(reachable_in(&grid, 6), 16);
+        // 50 plots are only reachable in 10 steps on the infinite map, see below
+        assert_eq!(reachable_in(&grid, 10), 33);
+    }
+
+    #[test]
+    fn test_reachable_in_infinite_example_data() {
+        let grid = parse_garden(EXAMPLE_INPUT).unwrap();
+        assert_eq!(reachable_in_infinite(&grid, 6), 16);
+        assert_eq!(reachable_in_infinite(&grid, 10), 50);
+        assert_eq!(reachable_in_infinite(&grid, 50), 1594);
+        assert_eq!(reachable_in_infinite(&grid, 100), 6536);
+    }
+
+    #[test]
+    fn test_fit_reachable_in_infinite() {
+        // The example has rocks in the row of the start, so the fit does not apply
+        let grid = parse_garden(EXAMPLE_INPUT).unwrap();
+        assert_eq!(fit_reachable_in_infinite(&grid, PART2_STEPS).unwrap(), None);
+
+        let grid = parse_garden(
+            "\
+.......
+.#...#.
+..#....
+...S...
+.#...#.
+..#.#..
+.......
+",
+        )
+        .unwrap();
+        for steps in [3, 30, 31, 59, 100] {
+            assert_eq!(
+                fit_reachable_in_infinite(&grid, steps).unwrap(),
+                Some(reachable_in_infinite(&grid, steps)),
+                "{} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_garden_invalid() {
+        assert!(parse_garden("...\n.S.\n.x.\n")
+            .unwrap_err()
+            .to_string()
+            .contains("line 3, column 2"));
+        assert!(parse_garden("...\n...\n").is_err());
+        assert!(parse_garden(".S.\n.S.\n").is_err());
+        assert!(parse_garden("...\n.S\n").is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// A position in the schematic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coord {
+    pub line_idx: usize,
+    // Column in terms of the `chars()` iterator.
+    pub col: usize,
+}
+
+impl Coord {
+    pub fn new(line_idx: usize, col: usize) -> Coord {
+        Coord { line_idx, col }
+    }
+}
+
+/// Formats the position 1-based, like `line 3, column 4`, for use in messages.
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line_idx + 1, self.col + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display_is_one_based() {
+        assert_eq!(Coord::new(0, 0).to_string(), "line 1, column 1");
+        assert_eq!(Coord::new(2, 3).to_string(), "line 3, column 4");
+    }
+}
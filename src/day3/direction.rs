@@ -0,0 +1,86 @@
+use crate::day3::Coord;
+
+/// One of the eight directions towards a neighbouring cell in the schematic,
+/// where north is the line above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    /// All directions, clockwise starting at north.
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    /// Offset (lines, columns) of one step into this direction.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::N => (-1, 0),
+            Direction::NE => (-1, 1),
+            Direction::E => (0, 1),
+            Direction::SE => (1, 1),
+            Direction::S => (1, 0),
+            Direction::SW => (1, -1),
+            Direction::W => (0, -1),
+            Direction::NW => (-1, -1),
+        }
+    }
+}
+
+impl Coord {
+    /// The neighbouring position into the given direction.
+    /// `None` if it would be left of the first column or above the first line.
+    pub fn neighbor(self, direction: Direction) -> Option<Coord> {
+        let (line_offset, col_offset) = direction.offset();
+        Some(Coord::new(
+            self.line_idx.checked_add_signed(line_offset)?,
+            self.col.checked_add_signed(col_offset)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_neighbor() {
+        let coord = Coord::new(1, 1);
+        let neighbors = Direction::ALL.map(|direction| coord.neighbor(direction).unwrap());
+        assert_eq!(
+            neighbors,
+            [
+                Coord::new(0, 1),
+                Coord::new(0, 2),
+                Coord::new(1, 2),
+                Coord::new(2, 2),
+                Coord::new(2, 1),
+                Coord::new(2, 0),
+                Coord::new(1, 0),
+                Coord::new(0, 0),
+            ]
+        );
+
+        assert_eq!(Coord::new(0, 3).neighbor(Direction::NE), None);
+        assert_eq!(Coord::new(3, 0).neighbor(Direction::SW), None);
+        assert_eq!(
+            Coord::new(3, 0).neighbor(Direction::S),
+            Some(Coord::new(4, 0))
+        );
+    }
+}
@@ -1,34 +1,274 @@
+mod coord;
+mod direction;
 mod schematic_parser;
+mod sparse;
 
+pub use coord::Coord;
+pub use direction::Direction;
 pub use schematic_parser::*;
+pub use sparse::SparseSchematic;
 
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, log_line_counts, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
 use anyhow::{Context, Result};
-use std::fs;
+#[cfg(feature = "cli")]
 use std::path::PathBuf;
-use tracing::info;
+#[cfg(feature = "cli")]
+use tracing::{info, warn};
 
+#[cfg(feature = "cli")]
 #[derive(clap::Args, Debug)]
 pub struct Args {
     /// Input data from the puzzle (schematic text file).
     /// Empty lines are ignored.
-    pub input: PathBuf,
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+
+    /// Warn about symbols that do not neighbour any number.
+    #[arg(long)]
+    pub warn_unused_symbols: bool,
+
+    /// Also print the sum of the part numbers that neighbour exactly one symbol.
+    #[arg(long)]
+    pub single_symbol: bool,
+
+    /// Also print the sum of all numbers, including those that are not part numbers.
+    #[arg(long)]
+    pub all_sum: bool,
+
+    /// Fail if a gear symbol (see `--gear-symbol`) neighbours exactly one or more than two
+    /// part numbers, which might be a mistake in the input.
+    #[arg(long)]
+    pub validate_gears: bool,
+
+    /// Treat this character as blank (like `.`) instead of as a symbol.
+    /// Can be given multiple times.
+    #[arg(long = "blank", value_name = "CHAR")]
+    pub blanks: Vec<char>,
+
+    /// Also treat this character as a gear symbol (besides `*`), and print the sum of the
+    /// gear ratios for every gear symbol. Can be given multiple times.
+    #[arg(long = "gear-symbol", value_name = "CHAR")]
+    pub gear_symbols: Vec<char>,
+
+    /// Print the N gears with the highest gear ratios, and where they are.
+    #[arg(long, value_name = "N")]
+    pub top_gears: Option<usize>,
+
+    /// Print the schematic with a `^` below every gear and the numbers that are not part
+    /// numbers dimmed (using ANSI escape codes).
+    #[arg(long)]
+    pub render: bool,
+
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
 }
 
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of all part numbers
+    pub part1: u64,
+    /// Sum of all gear ratios
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
 pub fn run(args: Args) -> Result<()> {
-    let input: String = fs::read_to_string(&args.input)
-        .with_context(|| format!("While trying to read file {}", args.input.display()))?;
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+    log_line_counts(&input);
+    let blanks = [DEFAULT_BLANKS, &args.blanks].concat();
+    let gear_symbols = [DEFAULT_GEAR_SYMBOLS, &args.gear_symbols].concat();
 
-    let schematic = input.parse::<Schematic>()?;
+    if args.validate_gears
+        || args.warn_unused_symbols
+        || args.single_symbol
+        || args.all_sum
+        || args.top_gears.is_some()
+        || args.render
+        || !args.gear_symbols.is_empty()
+    {
+        let schematic = Schematic::parse_with(&input, &blanks, &gear_symbols)?;
+
+        if args.validate_gears {
+            schematic.validate_gears(&gear_symbols)?;
+        }
+
+        if args.warn_unused_symbols {
+            for symbol in schematic.unused_symbols() {
+                warn!(
+                    "Symbol `{}` on line {}, column {} does not neighbour any number",
+                    symbol.symbol,
+                    symbol.coord.line_idx + 1,
+                    symbol.coord.col + 1
+                );
+            }
+        }
+
+        if args.single_symbol {
+            let sum: u64 = schematic
+                .single_symbol_part_numbers()
+                .map(|part| part.part_number)
+                .sum();
+            info!(
+                "Sum of all part numbers neighbouring exactly one symbol: {}",
+                sum
+            );
+        }
 
-    let part_numbers_sum: u64 = schematic
-        .part_numbers
-        .iter()
-        .map(|part| part.part_number)
-        .sum();
-    info!("(Part 1) Sum of all part numbers: {part_numbers_sum}");
+        if args.all_sum {
+            info!(
+                "Sum of all numbers, including non-part numbers: {}",
+                schematic.all_numbers_sum()
+            );
+        }
 
-    let gear_ratio_sum: u64 = schematic.gears.iter().map(|gear| gear.gear_ratio()).sum();
-    info!("(Part 2) Sum of all gear ratios: {gear_ratio_sum}");
+        if !args.gear_symbols.is_empty() {
+            let sums = schematic.gear_ratio_sums_by_symbol()?;
+            for symbol in &gear_symbols {
+                info!(
+                    "Sum of all `{}` gear ratios: {}",
+                    symbol,
+                    sums.get(symbol).copied().unwrap_or(0)
+                );
+            }
+        }
+
+        if let Some(top_gears) = args.top_gears {
+            for gear in schematic.gears_by_ratio()?.into_iter().take(top_gears) {
+                println!(
+                    "Gear on line {}, column {}: {} * {} = {}",
+                    gear.line_idx + 1,
+                    gear.index_chars + 1,
+                    gear.neighbors.0.part_number,
+                    gear.neighbors.1.part_number,
+                    gear.gear_ratio()?
+                );
+            }
+        }
+
+        if args.render {
+            print!("{}", schematic.render(&input));
+        }
+    }
+
+    let answers = solve_repeatedly(&input, args.repeat, |input| {
+        solve_schematic(&Schematic::parse_with(input, &blanks, &gear_symbols)?)
+    })?;
+    info!("(Part 1) Sum of all part numbers: {}", answers.part1);
+    info!("(Part 2) Sum of all gear ratios: {}", answers.part2);
 
     Ok(())
 }
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day3::{solve, Answers};
+/// let answers = solve("467..\n...*.\n..35.\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 502, part2: 467 * 35 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let schematic = input.parse::<Schematic>()?;
+    solve_schematic(&schematic)
+}
+
+fn solve_schematic(schematic: &Schematic) -> Result<Answers> {
+    let mut part1 = 0u64;
+    for part in &schematic.part_numbers {
+        part1 = part1.checked_add(part.part_number).with_context(|| {
+            format!(
+                "Sum of part numbers overflows when adding {} at {}",
+                part.part_number,
+                part.coord()
+            )
+        })?;
+    }
+
+    let mut part2 = 0u64;
+    for gear in &schematic.gears {
+        part2 = part2.checked_add(gear.gear_ratio()?).with_context(|| {
+            format!(
+                "Sum of gear ratios overflows when adding the gear at {}",
+                gear.coord()
+            )
+        })?;
+    }
+
+    Ok(Answers { part1, part2 })
+}
+
+/// The schematic from the puzzle description, shared by the tests of every day 3 module.
+#[cfg(test)]
+pub(crate) const EXAMPLE_INPUT: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_validate_gears_respects_blanks() {
+        use crate::{Args, Day, DayCommand};
+        use clap::Parser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day3.txt");
+        // Both `*` and `x` only neighbour a single part number
+        std::fs::write(&path, "617*.\n.....\n.12x.\n").unwrap();
+
+        let run_with = |extra_args: &[&str]| {
+            let mut cli = vec!["advent-of-code-2023", "day3", "--validate-gears"];
+            cli.extend_from_slice(extra_args);
+            cli.push(path.to_str().unwrap());
+            let Day::Solve(DayCommand::Day3(args)) = Args::try_parse_from(cli).unwrap().day else {
+                panic!("Expected day 3 arguments");
+            };
+            super::run(args)
+        };
+
+        let err = run_with(&[]).unwrap_err();
+        assert!(
+            err.to_string().contains("`*` at line 1, column 4"),
+            "{}",
+            err
+        );
+        // A blank `*` is not a gear symbol anymore, and `x` is not one by default
+        assert!(run_with(&["--blank", "*"]).is_ok());
+        let err = run_with(&["--blank", "*", "--gear-symbol", "x"]).unwrap_err();
+        assert!(
+            err.to_string().contains("`x` at line 3, column 4"),
+            "{}",
+            err
+        );
+    }
+}
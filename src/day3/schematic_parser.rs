@@ -1,20 +1,37 @@
-use anyhow::{Context, Result};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::day3::{Coord, Direction};
+use crate::grid::Grid;
+use anyhow::{bail, Context, Result};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::HashMap;
 use std::ops::Range;
 use std::str::FromStr;
 
-lazy_static! {
-    static ref NUMBER_REGEX: Regex = Regex::new(r"[0-9]+").unwrap();
-}
+/// Characters that are neither numbers nor symbols, unless configured otherwise
+/// with [`Schematic::parse_with_blanks`].
+pub const DEFAULT_BLANKS: &[char] = &['.'];
+
+/// ANSI escape codes that [`Schematic::render`] puts around non-part numbers.
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Characters that are gears if they neighbour exactly two part numbers, unless configured
+/// otherwise with [`Schematic::parse_with`].
+pub const DEFAULT_GEAR_SYMBOLS: &[char] = &['*'];
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Schematic {
     pub part_numbers: Vec<PartNumber>,
+    // Numbers without any neighbouring symbol, which are not part numbers.
+    pub non_part_numbers: Vec<PartNumber>,
     pub gears: Vec<Gear>,
+    pub symbols: Vec<Symbol>,
+    // The schematic with blanks normalized to dots and short lines padded, for neighbour queries.
+    grid: Grid<char>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PartNumber {
     pub part_number: u64,
     pub line_idx: usize,
@@ -22,74 +39,135 @@ pub struct PartNumber {
     pub range_bytes: Range<usize>,
     // Range in terms of the `chars()` iterator.
     pub range_chars: CharsRange,
+    // Number of symbols neighbouring this number, including diagonal neighbours.
+    pub num_adjacent_symbols: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gear {
     pub line_idx: usize,
     // Index in terms of bytes.
     pub index_bytes: usize,
     // Index in terms of the `chars()` iterator.
     pub index_chars: usize,
+    // The gear symbol, `*` unless configured otherwise.
+    pub symbol: char,
     // The two neighboring part numbers.
     pub neighbors: (PartNumber, PartNumber),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub coord: Coord,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharsRange(pub Range<usize>);
 
 impl FromStr for Schematic {
     type Err = anyhow::Error;
 
+    /// Parses a schematic where only `.` is blank, see [`Schematic::parse_with_blanks`].
+    /// Shorter lines are padded with `.`.
     fn from_str(schematic: &str) -> Result<Schematic> {
-        let lines = schematic.lines().collect::<Vec<_>>();
-        let mut part_numbers = vec![];
+        Schematic::from_grid(&Grid::from_str_padded(schematic, '.'))
+    }
+}
 
-        for (line_idx, &line) in lines.iter().enumerate() {
-            // Find all numbers in the line.
-            for number_match in NUMBER_REGEX.find_iter(line) {
-                let part_number = number_match.as_str().parse::<u64>().with_context(|| {
-                    format!(
-                        "While parsing line `{}`: `{}` is not a valid unsigned 64 bit integer",
-                        line,
-                        number_match.as_str()
-                    )
-                })?;
+impl Schematic {
+    /// Builds a schematic from a grid of characters, one row per line, where only `.` is blank.
+    ///
+    /// Byte ranges and indices are computed as if every row was a line of UTF-8 text.
+    pub fn from_grid(grid: &Grid<char>) -> Result<Schematic> {
+        Schematic::from_grid_with(grid, DEFAULT_BLANKS, DEFAULT_GEAR_SYMBOLS)
+    }
 
-                // If this number has at least one symbol around it, it is considered
-                // to be a "part number", and is therefore returned.
-                // A symbol is any character that is not a digit or a dot (".").
+    /// Parses a schematic where all of the given `blanks` characters are empty space instead of
+    /// symbols, e.g. `&['.', '_']` for inputs that also use `_` as a blank.
+    pub fn parse_with_blanks(schematic: &str, blanks: &[char]) -> Result<Schematic> {
+        Schematic::parse_with(schematic, blanks, DEFAULT_GEAR_SYMBOLS)
+    }
 
-                // Find out the chars() offset in the line.
-                // (Regex gives us the byte offset, which we need to convert)
-                // This implementation respects and correctly handles multi-byte UTF8 characters.
-                let match_char_range = CharsRange::from_bytes_range(line, number_match.range());
+    /// Like [`Schematic::parse_with_blanks`], but also configures which symbols can be gears,
+    /// e.g. `&['*', '%']`.
+    pub fn parse_with(
+        schematic: &str,
+        blanks: &[char],
+        gear_symbols: &[char],
+    ) -> Result<Schematic> {
+        // Shorter lines are padded with dots, so that the padding never counts as symbols
+        let grid = Grid::from_str_padded(schematic, '.');
+        Schematic::from_grid_with(&grid, blanks, gear_symbols)
+    }
 
-                let has_adjacent_symbol = is_symbol_left(line, number_match.range())
-                    || is_symbol_right(line, number_match.range())
-                    || is_symbol_above(&lines, line_idx, match_char_range.clone())
-                    || is_symbol_below(&lines, line_idx, match_char_range.clone());
+    fn from_grid_with(
+        original: &Grid<char>,
+        blanks: &[char],
+        gear_symbols: &[char],
+    ) -> Result<Schematic> {
+        // Blanks are normalized to dots, so that they never count as symbols.
+        // The byte offsets are still those of the original characters.
+        let rows = original
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|&c| if blanks.contains(&c) { '.' } else { c })
+                    .collect()
+            })
+            .collect();
+        let grid = Grid::from_rows(rows, '.');
+        let mut part_numbers = vec![];
+        let mut non_part_numbers = vec![];
 
-                if has_adjacent_symbol {
-                    part_numbers.push(PartNumber {
-                        part_number,
-                        line_idx,
-                        range_bytes: number_match.range(),
-                        range_chars: match_char_range,
-                    });
-                }
+        // The adjacency checks need the neighbouring lines, so they happen after extraction.
+        for extracted in extract_numbers_in_rows(&original.rows().collect::<Vec<_>>())? {
+            let ExtractedNumber {
+                value: part_number,
+                line_idx,
+                range_bytes,
+                range_chars: match_char_range,
+            } = extracted;
+
+            // If this number has at least one symbol around it, it is considered
+            // to be a "part number", and is therefore returned.
+            // A symbol is any character that is not a digit or a dot (".").
+
+            let has_adjacent_symbol = is_symbol_left(&grid, line_idx, &match_char_range)
+                || is_symbol_right(&grid, line_idx, &match_char_range)
+                || is_symbol_above(&grid, line_idx, &match_char_range)
+                || is_symbol_below(&grid, line_idx, &match_char_range);
+
+            let number = PartNumber {
+                part_number,
+                line_idx,
+                range_bytes,
+                num_adjacent_symbols: count_adjacent_symbols(&grid, line_idx, &match_char_range),
+                range_chars: match_char_range,
+            };
+            if has_adjacent_symbol {
+                part_numbers.push(number);
+            } else {
+                non_part_numbers.push(number);
             }
         }
 
         let mut gears = vec![];
 
-        for (line_idx, &line) in lines.iter().enumerate() {
-            for (gear_match_index_bytes, _) in line.match_indices('*') {
-                let chars_index =
-                    CharsRange::bytes_index_to_chars_index(line, gear_match_index_bytes);
+        for (line_idx, row) in original.rows().enumerate() {
+            let mut index_bytes = 0;
+            for (chars_index, &symbol) in row.iter().enumerate() {
+                let symbol_index_bytes = index_bytes;
+                index_bytes += symbol.len_utf8();
+                // A blank is never a symbol, so it can't be a gear either
+                if blanks.contains(&symbol) || !gear_symbols.contains(&symbol) {
+                    continue;
+                }
                 // This is a *potential* gear. We need to check if a number is neighbouring it.
 
-                // If exactly two part numbers neighbour this '*' char, it is considered a gear.
+                // If exactly two part numbers neighbour this char, it is considered a gear.
                 let mut neighbors: Vec<PartNumber> = part_numbers
                     .iter()
                     .filter(|part| part.is_neighboring_char(line_idx, chars_index))
@@ -99,24 +177,274 @@ impl FromStr for Schematic {
                 if neighbors.len() == 2 {
                     gears.push(Gear {
                         line_idx,
-                        index_bytes: gear_match_index_bytes,
-                        index_chars: CharsRange::bytes_index_to_chars_index(
-                            line,
-                            gear_match_index_bytes,
-                        ),
+                        index_bytes: symbol_index_bytes,
+                        index_chars: chars_index,
+                        symbol,
                         neighbors: (neighbors.remove(0), neighbors.remove(0)),
                     });
                 }
             }
         }
 
+        let symbols = grid
+            .iter()
+            .filter(|&(_, _, &c)| is_symbol(c))
+            .map(|(line_idx, index_chars, &symbol)| Symbol {
+                symbol,
+                coord: Coord::new(line_idx, index_chars),
+            })
+            .collect();
+
         Ok(Schematic {
             part_numbers,
+            non_part_numbers,
             gears,
+            symbols,
+            grid,
         })
     }
 }
 
+impl Schematic {
+    /// Fails if one of the `gear_symbols` neighbours exactly one or more than two part numbers.
+    /// Those are not gears, but might be a mistake in the input. A gear symbol without any
+    /// neighbouring part numbers is fine, it is only decoration.
+    ///
+    /// The error lists the position of every suspicious gear symbol.
+    pub fn validate_gears(&self, gear_symbols: &[char]) -> Result<()> {
+        let edges = self.symbol_number_edges();
+
+        let suspicious = self
+            .symbols
+            .iter()
+            .filter(|symbol| gear_symbols.contains(&symbol.symbol))
+            .filter_map(|symbol| {
+                let num_neighbors = edges
+                    .iter()
+                    .filter(|(coord, _)| *coord == symbol.coord)
+                    .count();
+                (num_neighbors == 1 || num_neighbors > 2).then(|| {
+                    format!(
+                        "`{}` at {} neighbours {} part numbers",
+                        symbol.symbol, symbol.coord, num_neighbors
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if !suspicious.is_empty() {
+            bail!(
+                "Found {} gear symbols that are not gears, but also not just decoration: {}",
+                suspicious.len(),
+                suspicious.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the part number that covers the given position, if any.
+    /// `col` is an index in terms of the `chars()` iterator.
+    pub fn part_number_at(&self, line_idx: usize, col: usize) -> Option<&PartNumber> {
+        self.part_numbers
+            .iter()
+            .find(|part| part.line_idx == line_idx && part.range_chars.0.contains(&col))
+    }
+
+    /// Iterates over every symbol as `(line_idx, col, symbol)`, top to bottom and left to right.
+    pub fn symbols(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+        self.symbols
+            .iter()
+            .map(|symbol| (symbol.coord.line_idx, symbol.coord.col, symbol.symbol))
+    }
+
+    /// Sums up every number in the schematic, both part numbers and numbers that do not
+    /// neighbour any symbol.
+    pub fn all_numbers_sum(&self) -> u64 {
+        self.part_numbers
+            .iter()
+            .chain(&self.non_part_numbers)
+            .map(|number| number.part_number)
+            .sum()
+    }
+
+    /// Returns the top-left and bottom-right corner of the smallest rectangle that contains
+    /// every part number (both corners inclusive), or `None` if there are no part numbers.
+    pub fn part_number_bounds(&self) -> Option<(Coord, Coord)> {
+        let first = self.part_numbers.first()?;
+        let mut min = Coord::new(first.line_idx, first.range_chars.0.start);
+        let mut max = Coord::new(first.line_idx, first.range_chars.0.end - 1);
+
+        for part in &self.part_numbers {
+            min.line_idx = min.line_idx.min(part.line_idx);
+            min.col = min.col.min(part.range_chars.0.start);
+            max.line_idx = max.line_idx.max(part.line_idx);
+            max.col = max.col.max(part.range_chars.0.end - 1);
+        }
+
+        Some((min, max))
+    }
+
+    /// Iterates over every cell that is covered by a part number, together with the value of
+    /// that part number. E.g. `467` at the start of the first line yields three cells, each
+    /// with the value `467`.
+    pub fn occupied_cells(&self) -> impl Iterator<Item = (Coord, u64)> + '_ {
+        self.part_numbers.iter().flat_map(|part| {
+            part.range_chars
+                .0
+                .clone()
+                .map(move |col| (Coord::new(part.line_idx, col), part.part_number))
+        })
+    }
+
+    /// Returns the part numbers that neighbour *exactly one* symbol. Numbers that touch two or
+    /// more symbols are left out, which is an alternative reading of the part 1 rules.
+    pub fn single_symbol_part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.part_numbers
+            .iter()
+            .filter(|part| part.num_adjacent_symbols == 1)
+    }
+
+    /// Returns all gears, sorted by their [`Gear::gear_ratio`] from highest to lowest.
+    /// Gears with the same ratio are sorted by their position, top to bottom and left to right.
+    ///
+    /// Fails if any gear ratio overflows.
+    pub fn gears_by_ratio(&self) -> Result<Vec<&Gear>> {
+        let mut gears = self
+            .gears
+            .iter()
+            .map(|gear| Ok((gear.gear_ratio()?, gear)))
+            .collect::<Result<Vec<_>>>()?;
+        gears.sort_by_key(|&(ratio, gear)| {
+            (std::cmp::Reverse(ratio), gear.line_idx, gear.index_chars)
+        });
+        Ok(gears.into_iter().map(|(_, gear)| gear).collect())
+    }
+
+    /// Sums up the gear ratios separately for every gear symbol. Symbols without any gears are
+    /// left out.
+    ///
+    /// Fails if any gear ratio or sum overflows.
+    pub fn gear_ratio_sums_by_symbol(&self) -> Result<HashMap<char, u64>> {
+        let mut sums = HashMap::new();
+        for gear in &self.gears {
+            let sum = sums.entry(gear.symbol).or_insert(0u64);
+            *sum = sum.checked_add(gear.gear_ratio()?).with_context(|| {
+                format!(
+                    "Sum of `{}` gear ratios overflows when adding the gear at {}",
+                    gear.symbol,
+                    gear.coord()
+                )
+            })?;
+        }
+        Ok(sums)
+    }
+
+    /// Lists every pair of a symbol and a part number next to it, as the symbol's position and
+    /// the part number. Unlike [`Schematic::gears`], this includes every kind of symbol, and
+    /// symbols with any number of neighbouring part numbers.
+    ///
+    /// Pairs are ordered by symbol (top to bottom, left to right), then by part number.
+    pub fn symbol_number_edges(&self) -> Vec<(Coord, &PartNumber)> {
+        self.symbols
+            .iter()
+            .flat_map(|symbol| {
+                self.part_numbers
+                    .iter()
+                    .filter(|part| {
+                        part.is_neighboring_char(symbol.coord.line_idx, symbol.coord.col)
+                    })
+                    .map(|part| (symbol.coord, part))
+            })
+            .collect()
+    }
+
+    /// Returns all symbols that do not neighbour any number.
+    /// These do not contribute to the puzzle answer, so they could be noise in the input.
+    pub fn unused_symbols(&self) -> Vec<&Symbol> {
+        self.symbols
+            .iter()
+            .filter(|symbol| {
+                !self
+                    .part_numbers
+                    .iter()
+                    .any(|part| part.is_neighboring_char(symbol.coord.line_idx, symbol.coord.col))
+            })
+            .collect()
+    }
+
+    /// Returns all part numbers that neighbour at least one instance of the given `symbol`
+    /// (including diagonally), in the order they appear in the schematic.
+    pub fn part_numbers_adjacent_to_symbol(&self, symbol: char) -> Vec<&PartNumber> {
+        let coords = self
+            .symbols
+            .iter()
+            .filter(|candidate| candidate.symbol == symbol)
+            .map(|candidate| candidate.coord)
+            .collect::<Vec<_>>();
+
+        self.part_numbers
+            .iter()
+            .filter(|part| {
+                coords
+                    .iter()
+                    .any(|coord| part.is_neighboring_char(coord.line_idx, coord.col))
+            })
+            .collect()
+    }
+
+    /// Returns the symbol next to `coord` in the given direction, or `None` if that cell is not a
+    /// symbol or outside of the schematic.
+    pub fn symbol_in_direction(&self, coord: Coord, direction: Direction) -> Option<char> {
+        symbol_in_direction(&self.grid, coord, direction)
+    }
+
+    /// Re-draws the `original` schematic (the text this schematic was parsed from) for
+    /// debugging: Numbers that are not part numbers are dimmed with ANSI escape codes, and every
+    /// line with gears is followed by a line with a `^` below each gear.
+    ///
+    /// Columns are counted in terms of the `chars()` iterator, so the markers only line up
+    /// if every character is displayed one column wide.
+    pub fn render(&self, original: &str) -> String {
+        let mut rendered = String::new();
+
+        for (line_idx, line) in original.lines().enumerate() {
+            let dimmed = self
+                .non_part_numbers
+                .iter()
+                .filter(|number| number.line_idx == line_idx)
+                .map(|number| &number.range_chars.0)
+                .collect::<Vec<_>>();
+            for (col, c) in line.chars().enumerate() {
+                if dimmed.iter().any(|range| range.start == col) {
+                    rendered.push_str(DIM);
+                }
+                rendered.push(c);
+                if dimmed.iter().any(|range| range.end == col + 1) {
+                    rendered.push_str(RESET);
+                }
+            }
+            rendered.push('\n');
+
+            let mut gear_cols = self
+                .gears
+                .iter()
+                .filter(|gear| gear.line_idx == line_idx)
+                .map(|gear| gear.index_chars)
+                .collect::<Vec<_>>();
+            gear_cols.sort_unstable();
+            if let Some(&last_col) = gear_cols.last() {
+                let markers = (0..=last_col)
+                    .map(|col| if gear_cols.contains(&col) { '^' } else { ' ' })
+                    .collect::<String>();
+                rendered.push_str(&markers);
+                rendered.push('\n');
+            }
+        }
+
+        rendered
+    }
+}
+
 impl PartNumber {
     /// Determines whether the a character on the given line at the given position neighbours this part number.
     /// Diagnonal neighbours are included.
@@ -139,12 +467,128 @@ impl PartNumber {
     }
 }
 
+impl PartNumber {
+    /// The position of the first digit of this part number.
+    pub fn coord(&self) -> Coord {
+        Coord::new(self.line_idx, self.range_chars.0.start)
+    }
+}
+
 impl Gear {
-    pub fn gear_ratio(&self) -> u64 {
-        self.neighbors.0.part_number * self.neighbors.1.part_number
+    /// The position of the symbol of this gear.
+    pub fn coord(&self) -> Coord {
+        Coord::new(self.line_idx, self.index_chars)
     }
+
+    /// Multiplies the two neighbouring part numbers.
+    /// Fails (naming the gear's position) if the product does not fit into a `u64`.
+    pub fn gear_ratio(&self) -> Result<u64> {
+        let (a, b) = (self.neighbors.0.part_number, self.neighbors.1.part_number);
+        a.checked_mul(b).with_context(|| {
+            format!(
+                "Gear ratio of the gear at {} overflows: {} * {}",
+                self.coord(),
+                a,
+                b
+            )
+        })
+    }
+}
+
+/// A number found in a line of the schematic, before it is known whether it is a part number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ExtractedNumber {
+    pub(super) value: u64,
+    pub(super) line_idx: usize,
+    pub(super) range_bytes: Range<usize>,
+    pub(super) range_chars: CharsRange,
+}
+
+/// Finds all numbers in the given lines, in order (line by line, left to right).
+/// With the `parallel` feature, the lines are scanned across multiple threads.
+pub(super) fn extract_numbers(lines: &[&str]) -> Result<Vec<ExtractedNumber>> {
+    extract_numbers_with(lines, |line_idx, line| {
+        extract_numbers_in_line(line_idx, line.chars())
+    })
+}
+
+/// Like [`extract_numbers`], but for the rows of a grid.
+fn extract_numbers_in_rows(rows: &[&[char]]) -> Result<Vec<ExtractedNumber>> {
+    extract_numbers_with(rows, |line_idx, row| {
+        extract_numbers_in_line(line_idx, row.iter().copied())
+    })
+}
+
+/// Calls `extract` on every line (possibly in parallel) and concatenates the results in order.
+fn extract_numbers_with<L: Sync>(
+    lines: &[L],
+    extract: impl Fn(usize, &L) -> Result<Vec<ExtractedNumber>> + Sync + Send,
+) -> Result<Vec<ExtractedNumber>> {
+    #[cfg(feature = "parallel")]
+    let lines = lines.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let lines = lines.iter();
+
+    // Collecting an indexed iterator keeps the order of the lines, even in parallel
+    let numbers_per_line = lines
+        .enumerate()
+        .map(|(line_idx, line)| extract(line_idx, line))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(numbers_per_line.into_iter().flatten().collect())
+}
+
+/// Finds all numbers (runs of ASCII digits) in a single line of the schematic, from left to right.
+/// Keeps track of both the `chars()` and byte offsets, so multi-byte UTF-8 characters are
+/// handled correctly.
+fn extract_numbers_in_line(
+    line_idx: usize,
+    line: impl Iterator<Item = char> + Clone,
+) -> Result<Vec<ExtractedNumber>> {
+    let mut numbers = vec![];
+    let mut digits = String::new();
+    // Where the current run of digits started, in bytes and in chars
+    let mut start = (0, 0);
+    let mut index_bytes = 0;
+
+    // The blank at the end finishes a run of digits at the end of the line
+    for (index_chars, c) in line.clone().chain(std::iter::once('.')).enumerate() {
+        if c.is_ascii_digit() {
+            if digits.is_empty() {
+                start = (index_bytes, index_chars);
+            }
+            digits.push(c);
+        } else if !digits.is_empty() {
+            let value = digits.parse::<u64>().with_context(|| {
+                format!(
+                    "While parsing line `{}`: `{}` at {} is not a valid unsigned 64 bit integer",
+                    line.clone().collect::<String>(),
+                    digits,
+                    Coord::new(line_idx, start.1)
+                )
+            })?;
+
+            numbers.push(ExtractedNumber {
+                value,
+                line_idx,
+                range_bytes: start.0..index_bytes,
+                range_chars: CharsRange(start.1..index_chars),
+            });
+            digits.clear();
+        }
+        index_bytes += c.len_utf8();
+    }
+
+    Ok(numbers)
 }
 
+impl CharsRange {
+    fn grown_by_one(&self) -> Self {
+        CharsRange(self.0.start.saturating_sub(1)..self.0.end + 1)
+    }
+}
+
+#[cfg(test)]
 impl CharsRange {
     /// Given that `bytes_range` refers to a substring in the `input`, determines
     /// what index is necessary to find the same substring in terms of the `chars()`
@@ -159,78 +603,146 @@ impl CharsRange {
     fn bytes_index_to_chars_index(input: &str, bytes_index: usize) -> usize {
         input[..bytes_index].chars().count()
     }
-
-    fn grown_by_one(&self) -> Self {
-        CharsRange(self.0.start.saturating_sub(1)..self.0.end + 1)
-    }
 }
 
-/// Returns whether there is a symbol to the left of the given (bytes) range in the string.
+/// Returns whether there is a symbol directly to the left of the given range on the given line.
 /// Returns `false` in case there is no character to the left.
-fn is_symbol_left(input: &str, number_bytes_range: Range<usize>) -> bool {
-    input[..number_bytes_range.start]
-        .chars()
-        .last()
-        .map(is_symbol)
-        .unwrap_or(false)
+/// `number_chars_range` is a range in terms of the `chars()` iterator.
+fn is_symbol_left(grid: &Grid<char>, line_idx: usize, number_chars_range: &CharsRange) -> bool {
+    let first = Coord::new(line_idx, number_chars_range.0.start);
+    symbol_in_direction(grid, first, Direction::W).is_some()
 }
 
-/// Returns whether there is a symbol to the left of the given (bytes) range in the string.
+/// Returns whether there is a symbol directly to the right of the given range on the given line.
 /// Returns `false` in case there is no character to the right.
-fn is_symbol_right(input: &str, number_bytes_range: Range<usize>) -> bool {
-    input[number_bytes_range.end..]
-        .chars()
-        .next()
-        .map(is_symbol)
-        .unwrap_or(false)
+/// `number_chars_range` is a range in terms of the `chars()` iterator.
+fn is_symbol_right(grid: &Grid<char>, line_idx: usize, number_chars_range: &CharsRange) -> bool {
+    let last = Coord::new(line_idx, number_chars_range.0.end - 1);
+    symbol_in_direction(grid, last, Direction::E).is_some()
 }
 
 /// Returns whether a symbol can be found in the line above the line where the number was found.
 /// Includes diagonal neighbours.
 /// `number_chars_range` is a range in terms of the `chars()` iterator.
-fn is_symbol_above(lines: &[&str], number_line_idx: usize, number_chars_range: CharsRange) -> bool {
-    is_symbol_in_line_idx(lines, number_line_idx.checked_sub(1), number_chars_range)
+fn is_symbol_above(
+    grid: &Grid<char>,
+    number_line_idx: usize,
+    number_chars_range: &CharsRange,
+) -> bool {
+    is_symbol_beside_range(
+        grid,
+        number_line_idx,
+        number_chars_range,
+        [Direction::NW, Direction::N, Direction::NE],
+    )
 }
 
 /// Returns whether a symbol can be found in the line below the line where the number was found.
 /// Includes diagonal neighbours.
 /// `number_chars_range` is a range in terms of the `chars()` iterator.
-fn is_symbol_below(lines: &[&str], number_line_idx: usize, number_chars_range: CharsRange) -> bool {
-    is_symbol_in_line_idx(lines, number_line_idx.checked_add(1), number_chars_range)
+fn is_symbol_below(
+    grid: &Grid<char>,
+    number_line_idx: usize,
+    number_chars_range: &CharsRange,
+) -> bool {
+    is_symbol_beside_range(
+        grid,
+        number_line_idx,
+        number_chars_range,
+        [Direction::SW, Direction::S, Direction::SE],
+    )
 }
 
-fn is_symbol_in_line_idx(
-    lines: &[&str],
-    line_idx: Option<usize>,
-    number_chars_range: CharsRange,
+/// Returns whether a symbol can be found on one side of the given range on the given line,
+/// including the diagonal neighbours of its ends. The side is given as the directions towards it
+/// from the first character, from every character and from the last character of the range.
+/// `number_chars_range` is a range in terms of the `chars()` iterator.
+fn is_symbol_beside_range(
+    grid: &Grid<char>,
+    line_idx: usize,
+    number_chars_range: &CharsRange,
+    [diagonal_before, straight, diagonal_after]: [Direction; 3],
 ) -> bool {
-    line_idx
-        .and_then(|line_idx| lines.get(line_idx))
-        .map(|line_above| is_symbol_in_or_next_to_range(line_above, number_chars_range))
-        .unwrap_or(false)
+    let range = &number_chars_range.0;
+    let coord = |col| Coord::new(line_idx, col);
+
+    symbol_in_direction(grid, coord(range.start), diagonal_before).is_some()
+        || range
+            .clone()
+            .any(|col| symbol_in_direction(grid, coord(col), straight).is_some())
+        || symbol_in_direction(grid, coord(range.end - 1), diagonal_after).is_some()
 }
 
-/// Returns whether a symbol can be found in the given range, expanded by 1 in each direction, in the string.
+/// Returns the symbol next to `coord` in the given direction.
+/// Returns `None` if there is no symbol, or the position is outside of the schematic.
+fn symbol_in_direction(grid: &Grid<char>, coord: Coord, direction: Direction) -> Option<char> {
+    let neighbor = coord.neighbor(direction)?;
+    grid.get(neighbor.line_idx as isize, neighbor.col as isize)
+        .copied()
+        .filter(|&c| is_symbol(c))
+}
+
+/// Counts the symbols around the given range on the given line, including diagonal neighbours.
 /// `number_chars_range` is a range in terms of the `chars()` iterator.
-fn is_symbol_in_or_next_to_range(input: &str, number_chars_range: CharsRange) -> bool {
-    let range_grown_by_one = number_chars_range.grown_by_one();
-
-    input
-        .chars()
-        .skip(range_grown_by_one.0.start)
-        .take(range_grown_by_one.0.len())
-        .any(is_symbol)
+fn count_adjacent_symbols(
+    grid: &Grid<char>,
+    number_line_idx: usize,
+    number_chars_range: &CharsRange,
+) -> usize {
+    let line_idx = number_line_idx as isize;
+    let grown_range = number_chars_range.grown_by_one().0;
+
+    let left_and_right = [grown_range.start, grown_range.end - 1]
+        .into_iter()
+        // If the number starts at column 0, the range can't grow to the left
+        .filter(|&col| !number_chars_range.0.contains(&col))
+        .filter(|&col| is_symbol_at(grid, line_idx, col as isize))
+        .count();
+    let above_and_below = [line_idx - 1, line_idx + 1]
+        .into_iter()
+        .flat_map(|line_idx| grown_range.clone().map(move |col| (line_idx, col)))
+        .filter(|&(line_idx, col)| is_symbol_at(grid, line_idx, col as isize))
+        .count();
+
+    left_and_right + above_and_below
+}
+
+/// Returns whether there is a symbol at the given position.
+/// Returns `false` if the position is outside of the schematic.
+fn is_symbol_at(grid: &Grid<char>, line_idx: isize, index_chars: isize) -> bool {
+    grid.get(line_idx, index_chars)
+        .copied()
+        .map(is_symbol)
+        .unwrap_or(false)
 }
 
 /// Returns whether this character is considered to be a "symbol" for the purposes of this puzzle.
 /// This means: Any character that is not a digit (0-9) or a dot (".").
-fn is_symbol(input: char) -> bool {
+/// Other blank characters have already been replaced by dots, see [`Schematic::parse_with_blanks`].
+pub(super) fn is_symbol(input: char) -> bool {
     !input.is_ascii_digit() && input != '.'
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::day3::EXAMPLE_INPUT;
+
+    fn grid(lines: &[&str]) -> Grid<char> {
+        Grid::from_str_padded(&lines.join("\n"), '.')
+    }
+
+    /// Calls [`is_symbol_left`] on a single line, given the byte range of the number in that line.
+    fn is_symbol_left_in_line(line: &str, number_bytes_range: Range<usize>) -> bool {
+        let number_chars_range = CharsRange::from_bytes_range(line, number_bytes_range);
+        is_symbol_left(&grid(&[line]), 0, &number_chars_range)
+    }
+
+    /// Calls [`is_symbol_right`] on a single line, given the byte range of the number in that line.
+    fn is_symbol_right_in_line(line: &str, number_bytes_range: Range<usize>) -> bool {
+        let number_chars_range = CharsRange::from_bytes_range(line, number_bytes_range);
+        is_symbol_right(&grid(&[line]), 0, &number_chars_range)
+    }
 
     #[test]
     fn test_is_symbol() {
@@ -248,54 +760,66 @@ mod test {
     #[test]
     fn test_is_symbol_above() {
         assert!(!is_symbol_above(
-            &vec!["+......", "..123.."],
+            &grid(&["+......", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_above(
-            &vec![".+.....", "..123.."],
+            &grid(&[".+.....", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_above(
-            &vec!["..+....", "..123.."],
+            &grid(&["..+....", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_above(
-            &vec!["...+...", "..123.."],
+            &grid(&["...+...", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_above(
-            &vec!["....+..", "..123.."],
+            &grid(&["....+..", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_above(
-            &vec![".....+.", "..123.."],
+            &grid(&[".....+.", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_above(
-            &vec![".+++++.", "..123.."],
+            &grid(&[".+++++.", "..123.."]),
+            1,
+            &CharsRange(2..5)
+        ));
+        assert!(!is_symbol_above(
+            &grid(&["......+", "..123.."]),
+            1,
+            &CharsRange(2..5)
+        ));
+        assert!(!is_symbol_above(
+            &grid(&["", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(!is_symbol_above(
-            &vec!["......+", "..123.."],
+            &grid(&[".", "..123.."]),
+            1,
+            &CharsRange(2..5)
+        ));
+        assert!(is_symbol_above(
+            &grid(&[".+", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
-        assert!(!is_symbol_above(&vec!["", "..123.."], 1, CharsRange(2..5)));
-        assert!(!is_symbol_above(&vec![".", "..123.."], 1, CharsRange(2..5)));
-        assert!(is_symbol_above(&vec![".+", "..123.."], 1, CharsRange(2..5)));
         assert!(!is_symbol_above(
-            &vec!["+.....+", "..123.."],
+            &grid(&["+.....+", "..123.."]),
             1,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
-        assert!(!is_symbol_above(&vec!["..123.."], 0, CharsRange(2..5)));
+        assert!(!is_symbol_above(&grid(&["..123.."]), 0, &CharsRange(2..5)));
     }
 
     #[test]
@@ -310,9 +834,9 @@ mod test {
             CharsRange(3..6)
         );
         assert!(is_symbol_above(
-            &vec!["߷.+.....", "...123..."],
+            &grid(&["߷.+.....", "...123..."]),
             1,
-            CharsRange(3..6)
+            &CharsRange(3..6)
         ));
 
         assert_eq!(
@@ -320,64 +844,76 @@ mod test {
             CharsRange(3..6)
         );
         assert!(is_symbol_above(
-            &vec!["..+.....", "߷..123..."],
+            &grid(&["..+.....", "߷..123..."]),
             1,
-            CharsRange(3..6)
+            &CharsRange(3..6)
         ));
 
         assert!(is_symbol_above(
-            &vec!["......+..", "߷..123..."],
+            &grid(&["......+..", "߷..123..."]),
             1,
-            CharsRange(3..6)
+            &CharsRange(3..6)
         ));
         assert!(is_symbol_above(
-            &vec!["߷.+.....", "߷..123..."],
+            &grid(&["߷.+.....", "߷..123..."]),
             1,
-            CharsRange(3..6)
+            &CharsRange(3..6)
         ));
     }
 
     #[test]
     fn test_is_symbol_below() {
         assert!(!is_symbol_below(
-            &vec!["..123..", "+......"],
+            &grid(&["..123..", "+......"]),
+            0,
+            &CharsRange(2..5)
+        ));
+        assert!(is_symbol_below(
+            &grid(&["..123..", ".+....."]),
             0,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_below(
-            &vec!["..123..", ".+....."],
+            &grid(&["..123..", "..+...."]),
             0,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_below(
-            &vec!["..123..", "..+...."],
+            &grid(&["..123..", "...+..."]),
             0,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_below(
-            &vec!["..123..", "...+..."],
+            &grid(&["..123..", "....+.."]),
             0,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_below(
-            &vec!["..123..", "....+.."],
+            &grid(&["..123..", ".....+."]),
+            0,
+            &CharsRange(2..5)
+        ));
+        assert!(!is_symbol_below(
+            &grid(&["..123..", ""]),
             0,
-            CharsRange(2..5)
+            &CharsRange(2..5)
+        ));
+        assert!(!is_symbol_below(
+            &grid(&["..123..", "."]),
+            0,
+            &CharsRange(2..5)
         ));
         assert!(is_symbol_below(
-            &vec!["..123..", ".....+."],
+            &grid(&["..123..", ".+"]),
             0,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
-        assert!(!is_symbol_below(&vec!["..123..", ""], 0, CharsRange(2..5)));
-        assert!(!is_symbol_below(&vec!["..123..", "."], 0, CharsRange(2..5)));
-        assert!(is_symbol_below(&vec!["..123..", ".+"], 0, CharsRange(2..5)));
         assert!(!is_symbol_below(
-            &vec!["..123..", "......+"],
+            &grid(&["..123..", "......+"]),
             0,
-            CharsRange(2..5)
+            &CharsRange(2..5)
         ));
-        assert!(!is_symbol_below(&vec!["..123.."], 0, CharsRange(2..5)));
+        assert!(!is_symbol_below(&grid(&["..123.."]), 0, &CharsRange(2..5)));
     }
 
     // Not bothering with utf8 test for below method since it's implemented
@@ -385,110 +921,123 @@ mod test {
 
     #[test]
     fn test_is_symbol_left() {
-        assert!(!is_symbol_left("..123..", 2..5));
-        assert!(!is_symbol_left("+.123.+", 2..5));
-        assert!(is_symbol_left(".+123..", 2..5));
-        assert!(is_symbol_left(".+123+.", 2..5));
-        assert!(!is_symbol_left("..123+.", 2..5));
+        assert!(!is_symbol_left_in_line("..123..", 2..5));
+        assert!(!is_symbol_left_in_line("+.123.+", 2..5));
+        assert!(is_symbol_left_in_line(".+123..", 2..5));
+        assert!(is_symbol_left_in_line(".+123+.", 2..5));
+        assert!(!is_symbol_left_in_line("..123+.", 2..5));
     }
 
     #[test]
     fn test_is_symbol_left_utf8() {
         assert_eq!("߷".len(), 2);
         assert_eq!("߷".chars().count(), 1);
-        assert!(!is_symbol_left("߷..123..߷", 4..7));
-        assert!(!is_symbol_left("߷+.123.+߷", 4..7));
-        assert!(is_symbol_left("߷.+123..߷", 4..7));
-        assert!(is_symbol_left("߷.+123+.߷", 4..7));
-        assert!(!is_symbol_left("߷..123+.߷", 4..7));
+        assert!(!is_symbol_left_in_line("߷..123..߷", 4..7));
+        assert!(!is_symbol_left_in_line("߷+.123.+߷", 4..7));
+        assert!(is_symbol_left_in_line("߷.+123..߷", 4..7));
+        assert!(is_symbol_left_in_line("߷.+123+.߷", 4..7));
+        assert!(!is_symbol_left_in_line("߷..123+.߷", 4..7));
     }
 
     #[test]
     fn test_is_symbol_right() {
-        assert!(!is_symbol_right("..123..", 2..5));
-        assert!(!is_symbol_right("+.123.+", 2..5));
-        assert!(is_symbol_right("..123+.", 2..5));
-        assert!(is_symbol_right(".+123+.", 2..5));
-        assert!(!is_symbol_right(".+123..", 2..5));
+        assert!(!is_symbol_right_in_line("..123..", 2..5));
+        assert!(!is_symbol_right_in_line("+.123.+", 2..5));
+        assert!(is_symbol_right_in_line("..123+.", 2..5));
+        assert!(is_symbol_right_in_line(".+123+.", 2..5));
+        assert!(!is_symbol_right_in_line(".+123..", 2..5));
     }
 
     #[test]
     fn test_is_symbol_right_utf8() {
         assert_eq!("߷".len(), 2);
         assert_eq!("߷".chars().count(), 1);
-        assert!(!is_symbol_right("߷..123..߷", 4..7));
-        assert!(!is_symbol_right("߷+.123.+߷", 4..7));
-        assert!(is_symbol_right("߷..123+.߷", 4..7));
-        assert!(is_symbol_right("߷.+123+.߷", 4..7));
-        assert!(!is_symbol_right("߷.+123..߷", 4..7));
+        assert!(!is_symbol_right_in_line("߷..123..߷", 4..7));
+        assert!(!is_symbol_right_in_line("߷+.123.+߷", 4..7));
+        assert!(is_symbol_right_in_line("߷..123+.߷", 4..7));
+        assert!(is_symbol_right_in_line("߷.+123+.߷", 4..7));
+        assert!(!is_symbol_right_in_line("߷.+123..߷", 4..7));
     }
 
     #[test]
     fn test_parse_schematic_example_data() {
         // 114 and 58 are not considered schematic symbols. The rest are.
-        let example_input = "\
-467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..";
-
         assert_eq!(
-            example_input.parse::<Schematic>().unwrap(),
+            EXAMPLE_INPUT.parse::<Schematic>().unwrap(),
             Schematic {
+                grid: Grid::from_str_padded(EXAMPLE_INPUT, '.'),
                 part_numbers: vec![
                     PartNumber {
                         part_number: 467,
                         line_idx: 0,
                         range_bytes: 0..3,
                         range_chars: CharsRange(0..3),
+                        num_adjacent_symbols: 1,
                     },
                     PartNumber {
                         part_number: 35,
                         line_idx: 2,
                         range_bytes: 2..4,
                         range_chars: CharsRange(2..4),
+                        num_adjacent_symbols: 1,
                     },
                     PartNumber {
                         part_number: 633,
                         line_idx: 2,
                         range_bytes: 6..9,
                         range_chars: CharsRange(6..9),
+                        num_adjacent_symbols: 1,
                     },
                     PartNumber {
                         part_number: 617,
                         line_idx: 4,
                         range_bytes: 0..3,
                         range_chars: CharsRange(0..3),
+                        num_adjacent_symbols: 1,
                     },
                     PartNumber {
                         part_number: 592,
                         line_idx: 6,
                         range_bytes: 2..5,
                         range_chars: CharsRange(2..5),
+                        num_adjacent_symbols: 1,
                     },
                     PartNumber {
                         part_number: 755,
                         line_idx: 7,
                         range_bytes: 6..9,
                         range_chars: CharsRange(6..9),
+                        num_adjacent_symbols: 1,
                     },
                     PartNumber {
                         part_number: 664,
                         line_idx: 9,
                         range_bytes: 1..4,
                         range_chars: CharsRange(1..4),
+                        num_adjacent_symbols: 1,
                     },
                     PartNumber {
                         part_number: 598,
                         line_idx: 9,
                         range_bytes: 5..8,
                         range_chars: CharsRange(5..8),
+                        num_adjacent_symbols: 1,
+                    },
+                ],
+                non_part_numbers: vec![
+                    PartNumber {
+                        part_number: 114,
+                        line_idx: 0,
+                        range_bytes: 5..8,
+                        range_chars: CharsRange(5..8),
+                        num_adjacent_symbols: 0,
+                    },
+                    PartNumber {
+                        part_number: 58,
+                        line_idx: 5,
+                        range_bytes: 7..9,
+                        range_chars: CharsRange(7..9),
+                        num_adjacent_symbols: 0,
                     },
                 ],
                 gears: vec![
@@ -496,18 +1045,21 @@ mod test {
                         line_idx: 1,
                         index_bytes: 3,
                         index_chars: 3,
+                        symbol: '*',
                         neighbors: (
                             PartNumber {
                                 part_number: 467,
                                 line_idx: 0,
                                 range_bytes: 0..3,
                                 range_chars: CharsRange(0..3),
+                                num_adjacent_symbols: 1,
                             },
                             PartNumber {
                                 part_number: 35,
                                 line_idx: 2,
                                 range_bytes: 2..4,
                                 range_chars: CharsRange(2..4),
+                                num_adjacent_symbols: 1,
                             },
                         )
                     },
@@ -515,23 +1067,520 @@ mod test {
                         line_idx: 8,
                         index_bytes: 5,
                         index_chars: 5,
+                        symbol: '*',
                         neighbors: (
                             PartNumber {
                                 part_number: 755,
                                 line_idx: 7,
                                 range_bytes: 6..9,
                                 range_chars: CharsRange(6..9),
+                                num_adjacent_symbols: 1,
                             },
                             PartNumber {
                                 part_number: 598,
                                 line_idx: 9,
                                 range_bytes: 5..8,
                                 range_chars: CharsRange(5..8),
+                                num_adjacent_symbols: 1,
                             },
                         )
                     },
-                ]
+                ],
+                symbols: vec![
+                    Symbol {
+                        symbol: '*',
+                        coord: Coord::new(1, 3),
+                    },
+                    Symbol {
+                        symbol: '#',
+                        coord: Coord::new(3, 6),
+                    },
+                    Symbol {
+                        symbol: '*',
+                        coord: Coord::new(4, 3),
+                    },
+                    Symbol {
+                        symbol: '+',
+                        coord: Coord::new(5, 5),
+                    },
+                    Symbol {
+                        symbol: '$',
+                        coord: Coord::new(8, 3),
+                    },
+                    Symbol {
+                        symbol: '*',
+                        coord: Coord::new(8, 5),
+                    },
+                ],
             }
         );
     }
+
+    #[test]
+    fn test_part_number_bounds() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        assert_eq!(
+            schematic.part_number_bounds(),
+            Some((Coord::new(0, 0), Coord::new(9, 8)))
+        );
+
+        let schematic = "......\n..12*.\n......\n.5....\n..#..."
+            .parse::<Schematic>()
+            .unwrap();
+        assert_eq!(
+            schematic.part_number_bounds(),
+            Some((Coord::new(1, 1), Coord::new(3, 3)))
+        );
+    }
+
+    #[test]
+    fn test_part_number_bounds_no_part_numbers() {
+        let schematic = "114..\n....*".parse::<Schematic>().unwrap();
+        assert_eq!(schematic.part_number_bounds(), None);
+    }
+
+    #[test]
+    fn test_unused_symbols() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        assert!(schematic.unused_symbols().is_empty());
+
+        let input = "\
+467..114..
+...*......
+.......@..
+..35......";
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert_eq!(
+            schematic.unused_symbols(),
+            vec![&Symbol {
+                symbol: '@',
+                coord: Coord::new(2, 7),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_occupied_cells() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        let cells = schematic.occupied_cells().collect::<Vec<_>>();
+
+        let num_digits = schematic
+            .part_numbers
+            .iter()
+            .map(|part| part.part_number.to_string().len())
+            .sum::<usize>();
+        assert_eq!(num_digits, 23);
+        assert_eq!(cells.len(), num_digits);
+
+        assert_eq!(
+            &cells[..4],
+            &[
+                (Coord::new(0, 0), 467),
+                (Coord::new(0, 1), 467),
+                (Coord::new(0, 2), 467),
+                (Coord::new(2, 2), 35),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_symbol_part_numbers() {
+        // 12 touches only `*`, 34 touches both `#` and `+`, 56 touches nothing
+        let input = "\
+.12*......
+....#34+..
+56........";
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert_eq!(
+            schematic
+                .part_numbers
+                .iter()
+                .map(|part| (part.part_number, part.num_adjacent_symbols))
+                .collect::<Vec<_>>(),
+            vec![(12, 1), (34, 2)]
+        );
+        assert_eq!(
+            schematic
+                .single_symbol_part_numbers()
+                .map(|part| part.part_number)
+                .collect::<Vec<_>>(),
+            vec![12]
+        );
+    }
+
+    #[test]
+    fn test_count_adjacent_symbols() {
+        let grid = grid(&["*.*+*", "#12$.", "/&%.."]);
+        assert_eq!(count_adjacent_symbols(&grid, 1, &CharsRange(1..3)), 8);
+        assert_eq!(count_adjacent_symbols(&grid, 1, &CharsRange(0..1)), 3);
+    }
+
+    #[test]
+    fn test_gears_by_ratio() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        let ratios = schematic
+            .gears_by_ratio()
+            .unwrap()
+            .iter()
+            .map(|gear| gear.gear_ratio().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ratios, vec![755 * 598, 467 * 35]);
+    }
+
+    #[test]
+    fn test_gear_ratio_sums_by_symbol() {
+        let input = "\
+2*3...4
+.....%.
+5%6..7.
+.......
+8*9....";
+        let schematic = Schematic::parse_with(input, DEFAULT_BLANKS, &['*', '%']).unwrap();
+        let sums = schematic.gear_ratio_sums_by_symbol().unwrap();
+        assert_eq!(
+            sums,
+            HashMap::from([('*', 2 * 3 + 8 * 9), ('%', 5 * 6 + 4 * 7)])
+        );
+
+        // By default, `%` is just a symbol
+        let schematic = input.parse::<Schematic>().unwrap();
+        let sums = schematic.gear_ratio_sums_by_symbol().unwrap();
+        assert_eq!(sums, HashMap::from([('*', 2 * 3 + 8 * 9)]));
+    }
+
+    #[test]
+    fn test_gears_by_ratio_ties() {
+        let schematic = "2*3..\n.....\n3*2..".parse::<Schematic>().unwrap();
+        let positions = schematic
+            .gears_by_ratio()
+            .unwrap()
+            .iter()
+            .map(|gear| (gear.line_idx, gear.index_chars))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_symbol_number_edges() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        let edges = schematic.symbol_number_edges();
+
+        let star_neighbors = edges
+            .iter()
+            .filter(|(coord, _)| *coord == Coord::new(1, 3))
+            .map(|(_, part)| part.part_number)
+            .collect::<Vec<_>>();
+        assert_eq!(star_neighbors, vec![467, 35]);
+
+        // Every part number in the example touches exactly one symbol
+        assert_eq!(edges.len(), schematic.part_numbers.len());
+    }
+
+    #[test]
+    fn test_part_number_at() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        for col in 0..3 {
+            assert_eq!(schematic.part_number_at(0, col).unwrap().part_number, 467);
+        }
+        assert_eq!(schematic.part_number_at(2, 2).unwrap().part_number, 35);
+        assert_eq!(schematic.part_number_at(2, 3).unwrap().part_number, 35);
+
+        assert_eq!(schematic.part_number_at(0, 3), None);
+        assert_eq!(schematic.part_number_at(2, 1), None);
+        // 114 is not a part number
+        assert_eq!(schematic.part_number_at(0, 5), None);
+        assert_eq!(schematic.part_number_at(100, 0), None);
+    }
+
+    #[test]
+    fn test_part_numbers_adjacent_to_symbol() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        let part_numbers = |symbol| {
+            schematic
+                .part_numbers_adjacent_to_symbol(symbol)
+                .iter()
+                .map(|part| part.part_number)
+                .collect::<Vec<_>>()
+        };
+
+        // The only `#` is on line 3 (0-based), below 633
+        assert_eq!(part_numbers('#'), vec![633]);
+        assert_eq!(part_numbers('*'), vec![467, 35, 617, 755, 598]);
+        assert_eq!(part_numbers('$'), vec![664]);
+        assert_eq!(part_numbers('@'), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_symbol_in_direction() {
+        // A different symbol in every direction around the center, including a multi-byte one
+        let schematic = "a€c\nh.d\ngfe".parse::<Schematic>().unwrap();
+        let center = Coord::new(1, 1);
+        let symbols =
+            Direction::ALL.map(|direction| schematic.symbol_in_direction(center, direction));
+        assert_eq!(
+            symbols,
+            [
+                Some('€'),
+                Some('c'),
+                Some('d'),
+                Some('e'),
+                Some('f'),
+                Some('g'),
+                Some('h'),
+                Some('a'),
+            ]
+        );
+
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        // The `5` of 35 has the first `*` to its north
+        assert_eq!(
+            schematic.symbol_in_direction(Coord::new(2, 3), Direction::N),
+            Some('*')
+        );
+        // Digits and dots are no symbols
+        assert_eq!(
+            schematic.symbol_in_direction(Coord::new(2, 3), Direction::W),
+            None
+        );
+        assert_eq!(
+            schematic.symbol_in_direction(Coord::new(2, 3), Direction::E),
+            None
+        );
+        // Outside of the schematic
+        assert_eq!(
+            schematic.symbol_in_direction(Coord::new(0, 0), Direction::NW),
+            None
+        );
+        assert_eq!(
+            schematic.symbol_in_direction(Coord::new(9, 9), Direction::SE),
+            None
+        );
+    }
+
+    #[test]
+    fn test_symbols() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        assert_eq!(
+            schematic.symbols().collect::<Vec<_>>(),
+            vec![
+                (1, 3, '*'),
+                (3, 6, '#'),
+                (4, 3, '*'),
+                (5, 5, '+'),
+                (8, 3, '$'),
+                (8, 5, '*')
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_blanks() {
+        let input = "12_\n...\n_34";
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert_eq!(
+            schematic
+                .part_numbers
+                .iter()
+                .map(|part| part.part_number)
+                .collect::<Vec<_>>(),
+            vec![12, 34]
+        );
+        assert_eq!(schematic.symbols.len(), 2);
+
+        let schematic = Schematic::parse_with_blanks(input, &['.', '_']).unwrap();
+        assert!(schematic.part_numbers.is_empty());
+        assert_eq!(
+            schematic
+                .non_part_numbers
+                .iter()
+                .map(|number| number.part_number)
+                .collect::<Vec<_>>(),
+            vec![12, 34]
+        );
+        assert!(schematic.symbols.is_empty());
+    }
+
+    #[test]
+    fn test_parse_with_blanks_blank_gear_symbol() {
+        let input = "467#\n.*..\n35#.\n";
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert_eq!(schematic.gears.len(), 1);
+
+        // With `*` as a blank, the `#`s still make both numbers part numbers, but there is no gear
+        let schematic = Schematic::parse_with_blanks(input, &['.', '*']).unwrap();
+        assert_eq!(schematic.part_numbers.len(), 2);
+        assert!(schematic.gears.is_empty());
+        assert_eq!(crate::day3::solve_schematic(&schematic).unwrap().part2, 0);
+        assert!(schematic.validate_gears(&['*']).is_ok());
+
+        let schematic = Schematic::parse_with(input, &['.', '*'], &['*', '#']).unwrap();
+        assert!(schematic.gears.is_empty());
+    }
+
+    #[test]
+    fn test_parse_with_blanks_space_is_symbol_by_default() {
+        let input = "12 \n...";
+        assert_eq!(input.parse::<Schematic>().unwrap().part_numbers.len(), 1);
+        assert!(Schematic::parse_with_blanks(input, &['.', ' '])
+            .unwrap()
+            .part_numbers
+            .is_empty());
+    }
+
+    #[test]
+    fn test_validate_gears() {
+        let validate = |input: &str| {
+            input
+                .parse::<Schematic>()
+                .unwrap()
+                .validate_gears(DEFAULT_GEAR_SYMBOLS)
+        };
+        assert!(validate("467..114..\n...*......\n..35..633.\n......#...").is_ok());
+
+        // In the puzzle's example, `617*` is not a gear
+        let err = validate(EXAMPLE_INPUT).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Found 1 gear symbols that are not gears, but also not just decoration: \
+             `*` at line 5, column 4 neighbours 1 part numbers"
+        );
+
+        // A `*` without neighbours is fine
+        assert!(validate("1...\n...*").is_ok());
+
+        let err = validate("12..\n..*.\n....\n5*5.\n.5..").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Found 2 gear symbols that are not gears, but also not just decoration: \
+             `*` at line 2, column 3 neighbours 1 part numbers; \
+             `*` at line 4, column 2 neighbours 3 part numbers"
+        );
+    }
+
+    #[test]
+    fn test_validate_gears_with_custom_symbols() {
+        // With `*` as a blank, `617*` is no longer a suspicious gear
+        let schematic = Schematic::parse_with(EXAMPLE_INPUT, &['.', '*'], &[]).unwrap();
+        assert!(schematic.validate_gears(&['*']).is_ok());
+
+        // Additional gear symbols are validated as well
+        let schematic = Schematic::parse_with("12x.\n....", DEFAULT_BLANKS, &['*', 'x']).unwrap();
+        let err = schematic.validate_gears(&['*', 'x']).unwrap_err();
+        assert!(
+            err.to_string().contains("`x` at line 1, column 3"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_all_numbers_sum() {
+        let schematic = EXAMPLE_INPUT.parse::<Schematic>().unwrap();
+        let part_sum = schematic
+            .part_numbers
+            .iter()
+            .map(|part| part.part_number)
+            .sum::<u64>();
+        assert_eq!(part_sum, 4361);
+        assert_eq!(schematic.all_numbers_sum(), part_sum + 114 + 58);
+    }
+
+    #[test]
+    fn test_gear_ratio_overflow_names_coord() {
+        let schematic = "..........\n.99999999999*99999999999"
+            .parse::<Schematic>()
+            .unwrap();
+        let err = schematic.gears[0].gear_ratio().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Gear ratio of the gear at line 2, column 13 overflows: 99999999999 * 99999999999"
+        );
+        assert!(schematic.gears_by_ratio().is_err());
+    }
+
+    #[test]
+    fn test_parse_giant_number_names_coord() {
+        let err = "..*\n.123456789012345678901234567890"
+            .parse::<Schematic>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "While parsing line `.123456789012345678901234567890`: \
+             `123456789012345678901234567890` at line 2, column 2 is not a valid unsigned 64 bit integer"
+        );
+    }
+
+    #[test]
+    fn test_render_example_data() {
+        let rendered = EXAMPLE_INPUT
+            .parse::<Schematic>()
+            .unwrap()
+            .render(EXAMPLE_INPUT);
+        let lines = rendered.lines().collect::<Vec<_>>();
+
+        // A marker line below each of the two gears, at their columns
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[1], "...*......");
+        assert_eq!(lines[2], "   ^");
+        assert_eq!(lines[9], "...$.*....");
+        assert_eq!(lines[10], "     ^");
+
+        // 114 and 58 are not part numbers
+        assert_eq!(lines[0], "467..\x1b[2m114\x1b[0m..");
+        assert_eq!(lines[6], ".....+.\x1b[2m58\x1b[0m.");
+        assert_eq!(lines[3], "..35..633.");
+    }
+
+    #[test]
+    fn test_from_grid_same_as_parse() {
+        let input = "467..114..\n...*......\n€.35..633.\n......#...\n617*......";
+        let rows = input
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let grid = Grid::from_rows(rows, '.');
+
+        let from_grid = Schematic::from_grid(&grid).unwrap();
+        let parsed = input.parse::<Schematic>().unwrap();
+        assert_eq!(from_grid.part_numbers, parsed.part_numbers);
+        assert_eq!(
+            from_grid
+                .part_numbers
+                .iter()
+                .map(|part| part.part_number)
+                .collect::<Vec<_>>(),
+            vec![467, 35, 633, 617]
+        );
+        // Byte ranges are computed from the characters, `€` takes three bytes
+        assert_eq!(from_grid.part_numbers[1].range_bytes, 4..6);
+        assert_eq!(from_grid.part_numbers[1].range_chars, CharsRange(2..4));
+        assert_eq!(from_grid.gears, parsed.gears);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_extraction_matches_sequential() {
+        use crate::test_util::Lcg;
+
+        let mut rng = Lcg::new(54321);
+        let mut next_char = move || match rng.next_below(10) {
+            0..=4 => '.',
+            5 => '*',
+            6 => '#',
+            digit => char::from_digit(digit, 10).unwrap(),
+        };
+
+        let schematic = (0..2000)
+            .map(|_| (0..140).map(|_| next_char()).collect::<String>())
+            .collect::<Vec<_>>();
+        let lines = schematic.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let sequential = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(line_idx, line)| extract_numbers_in_line(line_idx, line.chars()).unwrap())
+            .collect::<Vec<_>>();
+        assert!(sequential.len() > 10_000);
+        assert_eq!(extract_numbers(&lines).unwrap(), sequential);
+    }
 }
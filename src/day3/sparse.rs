@@ -0,0 +1,194 @@
+use super::schematic_parser::{extract_numbers, is_symbol, ExtractedNumber};
+use crate::day3::{Coord, Gear, PartNumber, DEFAULT_GEAR_SYMBOLS};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Like [`Schematic`](crate::day3::Schematic), but instead of a grid of every cell, only the
+/// numbers and symbols are stored with their positions. Memory is proportional to the non-blank
+/// cells, which makes a difference for huge schematics that are mostly `.`.
+///
+/// The part numbers and gears are the same as those of a [`Schematic`](crate::day3::Schematic),
+/// in the same order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseSchematic {
+    pub part_numbers: Vec<PartNumber>,
+    // Numbers without any neighbouring symbol, which are not part numbers.
+    pub non_part_numbers: Vec<PartNumber>,
+    pub gears: Vec<Gear>,
+}
+
+/// A symbol and where it was found.
+struct SparseSymbol {
+    symbol: char,
+    coord: Coord,
+    // Index in terms of bytes.
+    index_bytes: usize,
+}
+
+impl FromStr for SparseSchematic {
+    type Err = anyhow::Error;
+
+    /// Parses the schematic with only `.` as a blank and `*` as the gear symbol, like the
+    /// [`FromStr`] implementation of [`Schematic`](crate::day3::Schematic).
+    fn from_str(schematic: &str) -> Result<SparseSchematic> {
+        let lines = schematic.lines().collect::<Vec<_>>();
+
+        let symbols = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(line_idx, line)| {
+                line.char_indices()
+                    .enumerate()
+                    .filter(|&(_, (_, c))| is_symbol(c))
+                    .map(move |(col, (index_bytes, symbol))| SparseSymbol {
+                        symbol,
+                        coord: Coord::new(line_idx, col),
+                        index_bytes,
+                    })
+            })
+            .collect::<Vec<_>>();
+        let symbol_coords = symbols
+            .iter()
+            .map(|symbol| symbol.coord)
+            .collect::<HashSet<_>>();
+
+        let mut part_numbers = vec![];
+        let mut non_part_numbers = vec![];
+        for extracted in extract_numbers(&lines)? {
+            let ExtractedNumber {
+                value: part_number,
+                line_idx,
+                range_bytes,
+                range_chars,
+            } = extracted;
+
+            let num_adjacent_symbols = cells_around(line_idx, &range_chars.0)
+                .filter(|coord| symbol_coords.contains(coord))
+                .count();
+            let number = PartNumber {
+                part_number,
+                line_idx,
+                range_bytes,
+                range_chars,
+                num_adjacent_symbols,
+            };
+            if num_adjacent_symbols > 0 {
+                part_numbers.push(number);
+            } else {
+                non_part_numbers.push(number);
+            }
+        }
+
+        // Which part number (by index) covers every digit that belongs to one
+        let part_number_cells = part_numbers
+            .iter()
+            .enumerate()
+            .flat_map(|(part_idx, part)| {
+                part.range_chars
+                    .0
+                    .clone()
+                    .map(move |col| (Coord::new(part.line_idx, col), part_idx))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let gears = symbols
+            .iter()
+            .filter(|symbol| DEFAULT_GEAR_SYMBOLS.contains(&symbol.symbol))
+            .filter_map(|symbol| {
+                let mut neighbors = cells_around(
+                    symbol.coord.line_idx,
+                    &(symbol.coord.col..symbol.coord.col + 1),
+                )
+                .filter_map(|coord| part_number_cells.get(&coord).copied())
+                .collect::<Vec<_>>();
+                // In the same order as the part numbers, like `Schematic` finds them
+                neighbors.sort_unstable();
+                neighbors.dedup();
+
+                match neighbors[..] {
+                    [first, second] => Some(Gear {
+                        line_idx: symbol.coord.line_idx,
+                        index_bytes: symbol.index_bytes,
+                        index_chars: symbol.coord.col,
+                        symbol: symbol.symbol,
+                        neighbors: (part_numbers[first].clone(), part_numbers[second].clone()),
+                    }),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        Ok(SparseSchematic {
+            part_numbers,
+            non_part_numbers,
+            gears,
+        })
+    }
+}
+
+/// The cells around the given range of columns on the given line, including diagonal ones.
+/// Cells left of the first column or above the first line are left out.
+fn cells_around(line_idx: usize, cols: &Range<usize>) -> impl Iterator<Item = Coord> + '_ {
+    let lines = line_idx.saturating_sub(1)..=line_idx + 1;
+    lines.flat_map(move |around_line_idx| {
+        (cols.start.saturating_sub(1)..cols.end + 1)
+            .filter(move |col| around_line_idx != line_idx || !cols.contains(col))
+            .map(move |col| Coord::new(around_line_idx, col))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day3::{Schematic, EXAMPLE_INPUT};
+    use crate::test_util::Lcg;
+
+    fn assert_same_as_schematic(input: &str) {
+        let sparse = input.parse::<SparseSchematic>().unwrap();
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert_eq!(sparse.part_numbers, schematic.part_numbers);
+        assert_eq!(sparse.non_part_numbers, schematic.non_part_numbers);
+        assert_eq!(sparse.gears, schematic.gears);
+    }
+
+    #[test]
+    fn test_example_data_same_as_schematic() {
+        assert_same_as_schematic(EXAMPLE_INPUT);
+
+        let sparse = EXAMPLE_INPUT.parse::<SparseSchematic>().unwrap();
+        assert_eq!(sparse.part_numbers.len(), 8);
+        assert_eq!(sparse.gears.len(), 2);
+    }
+
+    #[test]
+    fn test_edge_cases_same_as_schematic() {
+        // Numbers and symbols on the edges, a ragged line, multi-byte symbols, a `*` between
+        // more than two part numbers, and a number touching a gear twice
+        assert_same_as_schematic("1*2\n3..\n");
+        assert_same_as_schematic("€12\n..*34\n5\n");
+        assert_same_as_schematic("1.2\n.*.\n3.4\n");
+        assert_same_as_schematic("123\n*..\n..4\n");
+        assert_same_as_schematic("");
+    }
+
+    #[test]
+    fn test_large_sparse_grid_same_as_schematic() {
+        let mut rng = Lcg::new(12345);
+        let mut next_char = move || match rng.next_below(100) {
+            0..=3 => '*',
+            4..=5 => '#',
+            digit @ 6..=15 => char::from_digit(digit - 6, 10).unwrap(),
+            _ => '.',
+        };
+        let input = (0..250)
+            .map(|_| (0..250).map(|_| next_char()).collect::<String>() + "\n")
+            .collect::<String>();
+
+        let sparse = input.parse::<SparseSchematic>().unwrap();
+        assert!(sparse.part_numbers.len() > 1000);
+        assert!(sparse.gears.len() > 100);
+        assert_same_as_schematic(&input);
+    }
+}
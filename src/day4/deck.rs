@@ -0,0 +1,519 @@
+use crate::day4::{Scoring, Scratchcard};
+use crate::parse::{parse_lines, parse_lines_with};
+use anyhow::{Context, Result};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+
+/// The state of a single card after the win cascade, see [`Deck::final_state`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CardState {
+    /// 1-based position of the card in the deck
+    pub id: usize,
+    /// Winning numbers, sorted
+    pub winning: Vec<u8>,
+    /// Our numbers, sorted
+    pub our: Vec<u8>,
+    /// Number of copies after the cascade, including the original
+    pub copies: u64,
+}
+
+/// All scratchcards from a puzzle input, in the order they appeared.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Deck(pub Vec<Scratchcard>);
+
+impl FromStr for Deck {
+    type Err = anyhow::Error;
+
+    /// Parses one scratchcard per line. Empty lines are ignored.
+    fn from_str(input: &str) -> Result<Deck> {
+        Ok(Deck(parse_lines(input)?))
+    }
+}
+
+impl Deck {
+    /// Like the [`FromStr`] implementation, but for cards that only list our numbers.
+    /// Every card gets the same `winning_numbers`, see [`Scratchcard::parse_with_winning_numbers`].
+    pub fn parse_with_winning_numbers(input: &str, winning_numbers: &HashSet<u8>) -> Result<Deck> {
+        Deck::parse_lines(input, |line| {
+            Scratchcard::parse_with_winning_numbers(line, winning_numbers)
+        })
+    }
+
+    /// Like the [`FromStr`] implementation, but also accepts cards without a `|` separator,
+    /// see [`Scratchcard::parse_lenient`].
+    pub fn parse_lenient(input: &str) -> Result<Deck> {
+        Deck::parse_lines(input, Scratchcard::parse_lenient)
+    }
+
+    fn parse_lines<F>(input: &str, parse_scratchcard: F) -> Result<Deck>
+    where
+        F: Fn(&str) -> Result<Scratchcard>,
+    {
+        Ok(Deck(parse_lines_with(input, parse_scratchcard)?))
+    }
+
+    /// Sum of the [`Scratchcard::points`] of every card in the deck.
+    /// With the `parallel` feature, the cards are scored across multiple threads.
+    pub fn total_points(&self) -> Result<u64> {
+        self.total_points_with(Scoring::Doubling)
+    }
+
+    /// Like [`Deck::total_points`], but scores every card with the given `scoring`,
+    /// see [`Scratchcard::points_with`].
+    pub fn total_points_with(&self, scoring: Scoring) -> Result<u64> {
+        #[cfg(feature = "parallel")]
+        let scratchcards = self.0.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let scratchcards = self.0.iter();
+
+        scratchcards
+            .map(|scratchcard| scratchcard.points_with(scoring))
+            .sum::<Result<u64>>()
+    }
+
+    /// The [`Scratchcard::num_matches`] of every card in the deck, in order.
+    /// With the `parallel` feature, the set intersections are computed across multiple threads.
+    pub fn match_counts(&self) -> Vec<usize> {
+        #[cfg(feature = "parallel")]
+        let scratchcards = self.0.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let scratchcards = self.0.iter();
+
+        scratchcards.map(Scratchcard::num_matches).collect()
+    }
+
+    /// Lets every card win copies of the cards following it, then counts how many
+    /// scratchcards there are in total (originals and copies).
+    pub fn total_cards_after_cascade(mut self) -> u64 {
+        let match_counts = self.match_counts();
+        propagate_copies_with_match_counts(&mut self.0, &match_counts);
+        total_copies(&self.0)
+    }
+
+    /// Lets every card win copies of the cards following it (like
+    /// [`Deck::total_cards_after_cascade`]), then returns the final state of every card.
+    /// The numbers are sorted, so that the result does not depend on the order of a `HashSet`.
+    pub fn final_state(mut self) -> Vec<CardState> {
+        propagate_copies(&mut self.0);
+
+        self.0
+            .into_iter()
+            .enumerate()
+            .map(|(idx, scratchcard)| {
+                let mut winning = scratchcard.winning_numbers.into_iter().collect::<Vec<_>>();
+                winning.sort_unstable();
+                let mut our = scratchcard.our_numbers.into_iter().collect::<Vec<_>>();
+                our.sort_unstable();
+
+                CardState {
+                    id: idx + 1,
+                    winning,
+                    our,
+                    copies: scratchcard.copies,
+                }
+            })
+            .collect()
+    }
+
+    /// Number of scratchcards in the deck (not counting copies).
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the scratchcards, in the order they appeared.
+    pub fn iter(&self) -> std::slice::Iter<'_, Scratchcard> {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for Deck {
+    type Item = Scratchcard;
+    type IntoIter = std::vec::IntoIter<Scratchcard>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Deck {
+    type Item = &'a Scratchcard;
+    type IntoIter = std::slice::Iter<'a, Scratchcard>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Applies the part 2 rule: Every copy of a card with N matching numbers wins one copy of
+/// each of the N cards following it. Cards are processed in order, so copies that were won
+/// earlier also win copies themselves.
+///
+/// `cards` should have their initial number of copies (usually 1).
+pub fn propagate_copies(cards: &mut [Scratchcard]) {
+    let match_counts = cards
+        .iter()
+        .map(Scratchcard::num_matches)
+        .collect::<Vec<_>>();
+    propagate_copies_with_match_counts(cards, &match_counts);
+}
+
+/// Runs [`propagate_copies`] with precomputed match counts.
+/// Each card's copies depend on all cards before it, so this part is always sequential.
+fn propagate_copies_with_match_counts(cards: &mut [Scratchcard], match_counts: &[usize]) {
+    for (scratchcard_idx, &num_matches) in match_counts.iter().enumerate() {
+        let scratchcard_copies = cards[scratchcard_idx].copies;
+
+        for following_scratchcard in cards.iter_mut().skip(scratchcard_idx + 1).take(num_matches) {
+            // For each copy we have of this scratchcard, we win a copy of the next N scratchcards
+            // where N is the number of matching numbers on the scratchcard.
+            following_scratchcard.copies += scratchcard_copies;
+        }
+    }
+}
+
+/// Computes how many generations deep the win cascade goes, given the
+/// [`Scratchcard::num_matches`] of every card in order.
+///
+/// This is the number of cards in the longest chain where each card wins a copy of the next
+/// one. A card that wins nothing still forms a chain of length 1, and an empty deck has a
+/// longest chain of 0.
+pub fn longest_chain(match_counts: &[usize]) -> usize {
+    // depths[idx] is the length of the longest chain that ends at card `idx`
+    let mut depths = vec![1; match_counts.len()];
+
+    for (scratchcard_idx, &num_matches) in match_counts.iter().enumerate() {
+        let depth = depths[scratchcard_idx];
+        for following_depth in depths
+            .iter_mut()
+            .skip(scratchcard_idx + 1)
+            .take(num_matches)
+        {
+            *following_depth = usize::max(*following_depth, depth + 1);
+        }
+    }
+
+    depths.into_iter().max().unwrap_or(0)
+}
+
+/// Returns the (0-based) indices of the cards that are never won by any card before them, given
+/// the [`Scratchcard::num_matches`] of every card in order. These keep their single original
+/// copy through the whole cascade. The first card is always unwon.
+pub fn unwon_cards(match_counts: &[usize]) -> Vec<usize> {
+    let mut won = vec![false; match_counts.len()];
+
+    for (scratchcard_idx, &num_matches) in match_counts.iter().enumerate() {
+        for following_won in won.iter_mut().skip(scratchcard_idx + 1).take(num_matches) {
+            *following_won = true;
+        }
+    }
+
+    won.into_iter()
+        .enumerate()
+        .filter(|&(_, won)| !won)
+        .map(|(scratchcard_idx, _)| scratchcard_idx)
+        .collect()
+}
+
+/// Returns, for every card, how many scratchcards a single copy of it ends up as: itself, plus
+/// everything the cards it wins spawn in turn. Takes the [`Scratchcard::num_matches`] of every
+/// card in order.
+///
+/// Summed over all cards, this is the same as [`total_copies`] after the cascade.
+pub fn cards_spawned(match_counts: &[usize]) -> Vec<u64> {
+    let mut spawned = vec![1; match_counts.len()];
+
+    // A card only wins cards after it, so those are complete once we get to it
+    for (scratchcard_idx, &num_matches) in match_counts.iter().enumerate().rev() {
+        let won = spawned
+            .iter()
+            .skip(scratchcard_idx + 1)
+            .take(num_matches)
+            .sum::<u64>();
+        spawned[scratchcard_idx] += won;
+    }
+
+    spawned
+}
+
+/// Returns, for every card, the ratio of the scratchcards a single copy of it spawns
+/// (see [`cards_spawned`]) to that one copy, to see which cards are the most productive.
+/// Cards that win nothing have a multiplier of 1.
+pub fn copy_multiplier(cards: &[Scratchcard]) -> Vec<f64> {
+    let match_counts = cards
+        .iter()
+        .map(Scratchcard::num_matches)
+        .collect::<Vec<_>>();
+    cards_spawned(&match_counts)
+        .into_iter()
+        .map(|spawned| spawned as f64)
+        .collect()
+}
+
+/// Counts how many cards there are with each [`Scratchcard::num_matches`], ignoring copies.
+/// Match counts that no card has are left out.
+pub fn match_histogram(cards: &[Scratchcard]) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for scratchcard in cards {
+        *histogram.entry(scratchcard.num_matches()).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Total number of scratchcards, counting every copy of every card.
+pub fn total_copies(cards: &[Scratchcard]) -> u64 {
+    cards.iter().map(|scratchcard| scratchcard.copies).sum()
+}
+
+/// Sum of the [`Scratchcard::points`] of every card, with each card counted as often as we
+/// have copies of it. Call [`propagate_copies`] first to count the copies won in the cascade.
+pub fn total_points_with_copies(cards: &[Scratchcard]) -> Result<u64> {
+    cards
+        .iter()
+        .map(|scratchcard| {
+            scratchcard
+                .points()?
+                .checked_mul(scratchcard.copies)
+                .with_context(|| {
+                    format!(
+                        "overflow while trying to multiply the points of a card by its {} copies",
+                        scratchcard.copies
+                    )
+                })
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn test_deck_example_data() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        assert_eq!(deck.0.len(), 6);
+        assert_eq!(deck.total_points().unwrap(), 13);
+        assert_eq!(deck.total_cards_after_cascade(), 30);
+    }
+
+    #[test]
+    fn test_deck_collection_accessors() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        assert_eq!(deck.len(), 6);
+        assert!(!deck.is_empty());
+        assert!("".parse::<Deck>().unwrap().is_empty());
+
+        let mut num_cards = 0;
+        for scratchcard in &deck {
+            assert_eq!(scratchcard.copies, 1);
+            num_cards += 1;
+        }
+        assert_eq!(num_cards, 6);
+        assert_eq!(
+            deck.iter()
+                .map(Scratchcard::num_matches)
+                .collect::<Vec<_>>(),
+            vec![4, 2, 2, 1, 0, 0]
+        );
+
+        let owned = deck.into_iter().collect::<Vec<_>>();
+        assert_eq!(owned.len(), 6);
+    }
+
+    #[test]
+    fn test_final_state() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        let final_state = deck.final_state();
+        assert_eq!(
+            final_state
+                .iter()
+                .map(|card| card.copies)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 4, 8, 14, 1]
+        );
+        assert_eq!(
+            final_state[0],
+            CardState {
+                id: 1,
+                winning: vec![17, 41, 48, 83, 86],
+                our: vec![6, 9, 17, 31, 48, 53, 83, 86],
+                copies: 1,
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_final_state_json() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        let json = serde_json::to_value(deck.final_state()).unwrap();
+        assert_eq!(
+            json[4],
+            serde_json::json!({
+                "id": 5,
+                "winning": [26, 28, 32, 83, 87],
+                "our": [12, 22, 30, 36, 70, 82, 88, 93],
+                "copies": 14,
+            })
+        );
+    }
+
+    #[test]
+    fn test_deck_skips_empty_lines() {
+        let input = "\nCard 1: 1 2 | 2 3\n\nCard 2: 4 | 5\n";
+        let deck = input.parse::<Deck>().unwrap();
+        assert_eq!(deck.0.len(), 2);
+    }
+
+    #[test]
+    fn test_deck_bad_line() {
+        let input = "Card 1: 1 2 | 2 3\nCard 2: 4 5\n";
+        let err = input.parse::<Deck>().unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 2: `Card 2: 4 5`");
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        use crate::test_util::Lcg;
+
+        let mut rng = Lcg::new(12345);
+        let mut next_number = move || rng.next_below(100) as u8;
+
+        let scratchcards = (0..5000)
+            .map(|_| {
+                let winning_numbers = (0..5).map(|_| next_number()).collect::<Vec<_>>();
+                Scratchcard::from_sets(winning_numbers, (0..8).map(|_| next_number()))
+            })
+            .collect::<Vec<_>>();
+        let deck = Deck(scratchcards);
+
+        let sequential_match_counts = deck
+            .0
+            .iter()
+            .map(Scratchcard::num_matches)
+            .collect::<Vec<_>>();
+        assert_eq!(deck.match_counts(), sequential_match_counts);
+
+        let sequential_points = deck
+            .0
+            .iter()
+            .map(|scratchcard| scratchcard.points().unwrap())
+            .sum::<u64>();
+        assert_eq!(deck.total_points().unwrap(), sequential_points);
+
+        let mut sequential_cards = deck.0.clone();
+        propagate_copies(&mut sequential_cards);
+        assert_eq!(
+            deck.total_cards_after_cascade(),
+            total_copies(&sequential_cards)
+        );
+    }
+
+    #[test]
+    fn test_propagate_copies_example_data() {
+        let mut deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        propagate_copies(&mut deck.0);
+        assert_eq!(
+            deck.0.iter().map(|card| card.copies).collect::<Vec<_>>(),
+            vec![1, 2, 4, 8, 14, 1]
+        );
+        assert_eq!(total_copies(&deck.0), 30);
+    }
+
+    #[test]
+    fn test_longest_chain_example_data() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        let match_counts = deck.match_counts();
+        assert_eq!(match_counts, vec![4, 2, 2, 1, 0, 0]);
+        // Card 1 -> Card 2 -> Card 3 -> Card 4 -> Card 5
+        assert_eq!(longest_chain(&match_counts), 5);
+    }
+
+    #[test]
+    fn test_longest_chain_edge_cases() {
+        assert_eq!(longest_chain(&[]), 0);
+        assert_eq!(longest_chain(&[0, 0, 0]), 1);
+        // Winning more cards than there are left in the deck
+        assert_eq!(longest_chain(&[5, 0]), 2);
+        assert_eq!(longest_chain(&[1, 0, 1, 1, 0]), 3);
+    }
+
+    #[test]
+    fn test_unwon_cards_example_data() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        // Card 1 has no predecessor, and card 6 comes right after card 5, which wins nothing
+        // (card 4 only wins card 5). This matches their single copy after the cascade.
+        assert_eq!(unwon_cards(&deck.match_counts()), vec![0, 5]);
+    }
+
+    #[test]
+    fn test_unwon_cards_edge_cases() {
+        assert_eq!(unwon_cards(&[]), Vec::<usize>::new());
+        assert_eq!(unwon_cards(&[0, 0, 0]), vec![0, 1, 2]);
+        assert_eq!(unwon_cards(&[2, 0, 0]), vec![0]);
+        // Winning more cards than there are left in the deck
+        assert_eq!(unwon_cards(&[5, 0]), vec![0]);
+    }
+
+    #[test]
+    fn test_cards_spawned_example_data() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        let spawned = cards_spawned(&deck.match_counts());
+        assert_eq!(spawned, vec![15, 7, 4, 2, 1, 1]);
+        assert_eq!(
+            spawned.iter().sum::<u64>(),
+            deck.total_cards_after_cascade()
+        );
+    }
+
+    #[test]
+    fn test_copy_multiplier_example_data() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        let multipliers = copy_multiplier(&deck.0);
+        assert_eq!(multipliers, vec![15.0, 7.0, 4.0, 2.0, 1.0, 1.0]);
+
+        let most_productive = multipliers
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(scratchcard_idx, _)| scratchcard_idx);
+        assert_eq!(most_productive, Some(0));
+    }
+
+    #[test]
+    fn test_match_histogram_example_data() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        assert_eq!(
+            match_histogram(&deck.0),
+            BTreeMap::from([(0, 2), (1, 1), (2, 2), (4, 1)])
+        );
+        assert!(match_histogram(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_total_points_with_copies_example_data() {
+        let mut deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        // Before the cascade, every card has a single copy
+        assert_eq!(total_points_with_copies(&deck.0).unwrap(), 13);
+
+        propagate_copies(&mut deck.0);
+        // Points [8, 2, 2, 1, 0, 0] times copies [1, 2, 4, 8, 14, 1]
+        assert_eq!(total_points_with_copies(&deck.0).unwrap(), 8 + 4 + 8 + 8);
+    }
+}
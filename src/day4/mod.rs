@@ -1,60 +1,289 @@
+mod deck;
 mod scratchcard;
 
-use anyhow::{Context, Result};
-use std::fs;
+pub use deck::{
+    cards_spawned, copy_multiplier, longest_chain, match_histogram, propagate_copies, total_copies,
+    total_points_with_copies, unwon_cards, CardState, Deck,
+};
+pub use scratchcard::{parse_winning_numbers, Scoring, Scratchcard};
+
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, log_line_counts, read_input, read_input_file};
+#[cfg(feature = "cli")]
+use crate::parse::report_parse_errors;
+#[cfg(feature = "cli")]
+use crate::regex_profile;
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+#[cfg(feature = "cli")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "cli")]
 use std::path::PathBuf;
+#[cfg(feature = "cli")]
 use tracing::info;
 
-use crate::day4::scratchcard::Scratchcard;
-
+#[cfg(feature = "cli")]
 #[derive(clap::Args, Debug)]
 pub struct Args {
     /// Input data from the puzzle (schematic text file).
     /// Empty lines are ignored.
-    pub input: PathBuf,
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// File with winning numbers that are shared by every scratchcard. The scratchcards in
+    /// the input then only list our numbers, like `Card 1: 2 3 9`.
+    #[arg(long)]
+    pub winning_file: Option<PathBuf>,
+    /// Accept scratchcards without a `|` separator, like `Card 7: 1 2 3`.
+    /// Their numbers are all winning numbers, so they never win anything.
+    #[arg(long, conflicts_with = "winning_file")]
+    pub lenient: bool,
+    /// Also log statistics about the scratchcards, like the longest chain of won copies,
+    /// the number of cards that are never won and how many cards have each number of matches.
+    #[arg(long)]
+    pub stats: bool,
+    /// How the matches of a scratchcard are turned into points for part 1.
+    #[arg(long, value_enum, default_value_t = Scoring::Doubling)]
+    pub scoring: Scoring,
+    /// Only solve part 2. The points of part 1 are never computed, so scratchcards with too many
+    /// matches to score (see `--scoring`) do not make the run fail.
+    #[arg(long)]
+    pub part2_only: bool,
+    /// Instead of solving, print every card (numbered from 1) with its sorted numbers and its
+    /// number of copies after the win cascade, as a JSON array.
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    pub dump_final_json: bool,
+    /// Instead of stopping at the first scratchcard that fails to parse, log every one of them
+    /// before failing.
+    #[arg(long)]
+    pub collect_errors: bool,
+    /// Log (at `debug` level) the time spent matching the regexes that parse the input, and how
+    /// many matches there were. Purely diagnostic.
+    #[arg(long, hide = true)]
+    pub profile_regex: bool,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of the points of all scratchcards
+    pub part1: u64,
+    /// Number of scratchcards after winning copies
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
 }
 
+#[cfg(feature = "cli")]
 pub fn run(args: Args) -> Result<()> {
-    let input: String = fs::read_to_string(&args.input)
-        .with_context(|| format!("While trying to read file {}", args.input.display()))?;
-
-    let mut scratchcards = input
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            line.parse::<Scratchcard>()
-                .with_context(|| format!("Failed to parse scratchcard `{}`", line))
-        })
-        .collect::<Result<Vec<_>>>()?;
-
-    let sum_of_points = scratchcards
-        .iter()
-        .map(|scratchcard| scratchcard.points())
-        .sum::<Result<u64>>()?;
-
-    info!("(Part 1) Sum of points: {sum_of_points}");
-
-    for scratchcard_idx in 0..scratchcards.len() {
-        let scratchcard = &scratchcards[scratchcard_idx];
-        let num_matches = scratchcard.num_matches();
-        let scratchcard_copies = scratchcard.copies;
-
-        for following_scratchcard in scratchcards
-            .iter_mut()
-            .skip(scratchcard_idx + 1)
-            .take(num_matches)
-        {
-            // For each copy we have of this scratchcard, we win a copy of the next N scratchcards
-            // where N is the number of matching numbers on the scratchcard.
-            following_scratchcard.copies += scratchcard_copies;
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+    log_line_counts(&input);
+    if args.profile_regex {
+        regex_profile::enable();
+    }
+
+    let winning_numbers = match &args.winning_file {
+        Some(winning_file) => Some(
+            parse_winning_numbers(&read_input_file(winning_file, &args.input_options)?)
+                .with_context(|| format!("While parsing {}", winning_file.display()))?,
+        ),
+        None => None,
+    };
+    if args.collect_errors {
+        report_parse_errors(&input, |line| match &winning_numbers {
+            Some(winning_numbers) => Scratchcard::parse_with_winning_numbers(line, winning_numbers),
+            None if args.lenient => Scratchcard::parse_lenient(line),
+            None => line.parse::<Scratchcard>(),
+        })?;
+    }
+    let parse_deck = |input: &str| match &winning_numbers {
+        Some(winning_numbers) => Deck::parse_with_winning_numbers(input, winning_numbers),
+        None if args.lenient => Deck::parse_lenient(input),
+        None => input.parse::<Deck>(),
+    };
+
+    #[cfg(feature = "serde")]
+    if args.dump_final_json {
+        let final_state = parse_deck(&input)?.final_state();
+        println!("{}", serde_json::to_string_pretty(&final_state)?);
+        return Ok(());
+    }
+
+    if args.stats {
+        let deck = parse_deck(&input)?;
+        let match_counts = deck.match_counts();
+        info!(
+            "Longest chain of won copies: {} cards",
+            longest_chain(&match_counts)
+        );
+        info!(
+            "Cards that are never won: {}",
+            unwon_cards(&match_counts).len()
+        );
+        for (num_matches, num_cards) in match_histogram(&deck.0) {
+            info!("Cards with {} matches: {}", num_matches, num_cards);
+        }
+    }
+
+    if args.part2_only {
+        let part2 = solve_repeatedly(&input, args.repeat, |input| {
+            Ok(parse_deck(input)?.total_cards_after_cascade())
+        })?;
+        if args.profile_regex {
+            regex_profile::log_profile();
         }
+        info!(
+            "(Part 2) Number of scratchcards after following proper rules: {}",
+            part2
+        );
+        return Ok(());
     }
 
-    let num_scratchcards = scratchcards
-        .iter()
-        .map(|scratchcard| scratchcard.copies)
-        .sum::<u64>();
-    info!("(Part 2) Number of scratchcards after following proper rules: {num_scratchcards}");
+    let answers = solve_repeatedly(&input, args.repeat, |input| {
+        solve_deck(parse_deck(input)?, args.scoring)
+    })?;
+    if args.profile_regex {
+        regex_profile::log_profile();
+    }
+    info!("(Part 1) Sum of points: {}", answers.part1);
+    info!(
+        "(Part 2) Number of scratchcards after following proper rules: {}",
+        answers.part2
+    );
 
     Ok(())
 }
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day4::{solve, Answers};
+/// let answers = solve("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 8, part2: 1 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    solve_deck(input.parse::<Deck>()?, Scoring::Doubling)
+}
+
+/// Solves only part 2 of the puzzle for the given input (like [`solve`], but without computing
+/// any points, so it also works for scratchcards that have too many matches to score).
+pub fn solve_part2(input: &str) -> Result<u64> {
+    Ok(input.parse::<Deck>()?.total_cards_after_cascade())
+}
+
+fn solve_deck(deck: Deck, scoring: Scoring) -> Result<Answers> {
+    Ok(Answers {
+        part1: deck.total_points_with(scoring)?,
+        part2: deck.total_cards_after_cascade(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn test_solve_crlf_line_endings() {
+        let crlf_input = EXAMPLE_INPUT.replace('\n', "\r\n");
+        assert_eq!(
+            solve(&crlf_input).unwrap(),
+            Answers {
+                part1: 13,
+                part2: 30
+            }
+        );
+        assert_eq!(solve(&crlf_input).unwrap(), solve(EXAMPLE_INPUT).unwrap());
+    }
+
+    #[test]
+    fn test_scoring_example_data() {
+        let deck = EXAMPLE_INPUT.parse::<Deck>().unwrap();
+        assert_eq!(deck.total_points_with(Scoring::Doubling).unwrap(), 13);
+        assert_eq!(
+            deck.total_points_with(Scoring::Linear).unwrap(),
+            4 + 2 + 2 + 1
+        );
+        assert_eq!(deck.total_points_with(Scoring::Flat).unwrap(), 4);
+
+        let answers = solve_deck(EXAMPLE_INPUT.parse().unwrap(), Scoring::Linear).unwrap();
+        assert_eq!(answers.part1, 9);
+        assert_eq!(answers.part2, 30);
+    }
+
+    #[test]
+    fn test_part2_only_with_unscorable_card() {
+        // Card 1 has 65 matches, so its points (2^64) do not fit into a u64. It wins a copy of
+        // each of the 65 cards after it, which win nothing themselves.
+        let numbers = (1..=65)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut input = format!("Card 1: {numbers} | {numbers}\n");
+        for id in 2..=66 {
+            input.push_str(&format!("Card {id}: 1 | 2\n"));
+        }
+
+        assert!(solve(&input).is_err());
+        assert_eq!(solve_part2(&input).unwrap(), 1 + 65 * 2);
+        assert_eq!(solve_part2(EXAMPLE_INPUT).unwrap(), 30);
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_profile_regex_logs_debug_line() {
+        use crate::{Args, Day, DayCommand};
+        use clap::Parser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day4.txt");
+        std::fs::write(&path, EXAMPLE_INPUT).unwrap();
+
+        let args = Args::try_parse_from([
+            "advent-of-code-2023".as_ref(),
+            "day4".as_ref(),
+            "--profile-regex".as_ref(),
+            path.as_os_str(),
+        ])
+        .unwrap();
+        let Day::Solve(DayCommand::Day4(args)) = args.day else {
+            panic!("Expected day 4 arguments");
+        };
+
+        let logs = crate::logging::capture_logs(|| run(args).unwrap());
+        assert!(
+            logs.lines()
+                .any(|line| line.contains("DEBUG") && line.contains("regex")),
+            "{}",
+            logs
+        );
+    }
+}
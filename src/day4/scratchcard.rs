@@ -1,10 +1,12 @@
+use crate::regex_profile;
 use anyhow::{anyhow, Context, Result};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashSet;
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scratchcard {
     pub winning_numbers: HashSet<u8>,
     pub our_numbers: HashSet<u8>,
@@ -13,12 +15,29 @@ pub struct Scratchcard {
     pub copies: u64,
 }
 
+/// How the matches of a scratchcard are turned into points, see [`Scratchcard::points_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Scoring {
+    /// 1 point for the first match, doubled for every further match (the puzzle's rules)
+    #[default]
+    Doubling,
+    /// 1 point per match
+    Linear,
+    /// 1 point if there is at least one match, no matter how many
+    Flat,
+}
+
 lazy_static! {
     // https://regex101.com/r/4MNT2z/3
     // Group 1 = winning numbers
     // Group 2 = our numbers
     static ref SCRATCHCARD_FORMAT: Regex = Regex::new(r"^Card +[0-9]+: +([0-9 ]+?) +\| +([0-9 ]+)$").unwrap();
 
+    // Like SCRATCHCARD_FORMAT, but with only a single list of numbers and no separator
+    // Group 1 = numbers
+    static ref SCRATCHCARD_WITHOUT_WINNING_NUMBERS_FORMAT: Regex = Regex::new(r"^Card +[0-9]+: +([0-9 ]+)$").unwrap();
+
     static ref ANY_NUMBER_OF_SPACES: Regex = Regex::new(r" +").unwrap();
 }
 
@@ -26,8 +45,9 @@ impl FromStr for Scratchcard {
     type Err = anyhow::Error;
 
     fn from_str(input: &str) -> Result<Scratchcard> {
-        let captures = SCRATCHCARD_FORMAT
-            .captures(input)
+        // The regex is anchored with `$`, so a `\r` left over from CRLF line endings would not match
+        let input = input.trim_end_matches('\r');
+        let captures = regex_profile::captures(&SCRATCHCARD_FORMAT, input)
             .ok_or_else(|| anyhow!(format!("Invalid scratchcard format: {}", input)))?;
 
         let winning_numbers_str = captures.get(1).unwrap().as_str();
@@ -44,6 +64,64 @@ impl FromStr for Scratchcard {
     }
 }
 
+impl TryFrom<&str> for Scratchcard {
+    type Error = anyhow::Error;
+
+    /// Same as the [`FromStr`] implementation, for generic code with `TryFrom` bounds.
+    fn try_from(input: &str) -> Result<Scratchcard> {
+        input.parse()
+    }
+}
+
+impl Scratchcard {
+    /// Parses a scratchcard that only lists our numbers, like `Card 1: 2 3 9`.
+    /// This is for the variant where every card shares the same `winning_numbers`,
+    /// which are supplied separately.
+    pub fn parse_with_winning_numbers(
+        input: &str,
+        winning_numbers: &HashSet<u8>,
+    ) -> Result<Scratchcard> {
+        let input = input.trim_end_matches('\r');
+        let captures = regex_profile::captures(&SCRATCHCARD_WITHOUT_WINNING_NUMBERS_FORMAT, input)
+            .ok_or_else(|| anyhow!(format!("Invalid scratchcard format: {}", input)))?;
+
+        let our_numbers = parse_space_separated_values(captures.get(1).unwrap().as_str())?;
+
+        Ok(Scratchcard {
+            winning_numbers: winning_numbers.clone(),
+            our_numbers,
+            copies: 1,
+        })
+    }
+}
+
+impl Scratchcard {
+    /// Like the [`FromStr`] implementation, but also accepts cards without a `|` separator,
+    /// like `Card 7: 1 2 3`. These only have winning numbers and no numbers of ours,
+    /// so they never match anything and are worth zero points.
+    pub fn parse_lenient(input: &str) -> Result<Scratchcard> {
+        let input = input.trim_end_matches('\r');
+        if SCRATCHCARD_FORMAT.is_match(input) {
+            return input.parse::<Scratchcard>();
+        }
+
+        let captures = regex_profile::captures(&SCRATCHCARD_WITHOUT_WINNING_NUMBERS_FORMAT, input)
+            .ok_or_else(|| anyhow!(format!("Invalid scratchcard format: {}", input)))?;
+
+        Ok(Scratchcard {
+            winning_numbers: parse_space_separated_values(captures.get(1).unwrap().as_str())?,
+            our_numbers: HashSet::new(),
+            copies: 1,
+        })
+    }
+}
+
+/// Parses the winning numbers shared by all scratchcards, separated by any whitespace
+/// (including newlines), e.g. `1 2 3`.
+pub fn parse_winning_numbers(input: &str) -> Result<HashSet<u8>> {
+    input.split_whitespace().map(parse_number).collect()
+}
+
 fn parse_space_separated_values<N>(input: &str) -> Result<HashSet<N>>
 where
     N: FromStr + std::hash::Hash + Eq,
@@ -66,15 +144,51 @@ where
 }
 
 impl Scratchcard {
+    /// Builds a single (not yet copied) scratchcard from its winning numbers and our numbers,
+    /// e.g. for tests or generated decks. Duplicate numbers are ignored.
+    pub fn from_sets(
+        winning_numbers: impl IntoIterator<Item = u8>,
+        our_numbers: impl IntoIterator<Item = u8>,
+    ) -> Scratchcard {
+        Scratchcard {
+            winning_numbers: winning_numbers.into_iter().collect(),
+            our_numbers: our_numbers.into_iter().collect(),
+            copies: 1,
+        }
+    }
+
     pub fn num_matches(&self) -> usize {
         self.winning_numbers.intersection(&self.our_numbers).count()
     }
 
+    /// The card is worth 1 point for the first match, and every further match doubles that,
+    /// so `n` matches are worth `2^(n - 1)` points (and no matches are worth 0 points).
+    ///
+    /// Fails if that does not fit into a `u64`, which is the case from 65 matches on.
     pub fn points(&self) -> Result<u64> {
+        self.points_with(Scoring::Doubling)
+    }
+
+    /// The points the card is worth under the given `scoring`: `2^(n - 1)` for
+    /// [`Scoring::Doubling`] (see [`Scratchcard::points`]), `n` for [`Scoring::Linear`],
+    /// and `min(n, 1)` for [`Scoring::Flat`], where `n` is the number of matches.
+    pub fn points_with(&self, scoring: Scoring) -> Result<u64> {
+        match (scoring, self.num_matches()) {
+            (Scoring::Doubling, 0) => Ok(0),
+            (Scoring::Doubling, num_wins) => u64::checked_pow(2, (num_wins - 1) as u32)
+                .with_context(|| format!("overflow while trying to calculate points for {num_wins} wins, 2^{num_wins} > u64::max_value")),
+            (Scoring::Linear, num_wins) => Ok(num_wins as u64),
+            (Scoring::Flat, num_wins) => Ok(num_wins.min(1) as u64),
+        }
+    }
+
+    /// Same as [`Scratchcard::points`], but computes `2^(n - 1)` as `1 << (n - 1)`.
+    /// Returns `None` instead of an error on overflow, i.e. from 65 matches on, where the shift
+    /// would be 64 bits or more.
+    pub fn points_shift(&self) -> Option<u64> {
         match self.num_matches() {
-            0 => Ok(0),
-            num_wins => u64::checked_pow(2, (num_wins - 1) as u32)
-                .with_context(|| format!("overflow while trying to calculate points for {num_wins} wins, 2^{num_wins} > u64::max_value"))
+            0 => Some(0),
+            num_wins => 1u64.checked_shl(u32::try_from(num_wins - 1).ok()?),
         }
     }
 }
@@ -83,6 +197,16 @@ impl Scratchcard {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_try_from_matches_parse() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+        assert_eq!(
+            Scratchcard::try_from(input).unwrap(),
+            input.parse::<Scratchcard>().unwrap()
+        );
+        assert!(Scratchcard::try_from("Card 1: 41 48").is_err());
+    }
+
     #[test]
     fn test_parse_space_separated_values() {
         let input = "1 2 3 4 5";
@@ -186,4 +310,124 @@ mod test {
         // no winning numbers = 0 points
         assert_eq!(scratchcard.points().unwrap(), 0);
     }
+
+    /// A scratchcard where every one of the `num_matches` numbers matches.
+    fn scratchcard_with_matches(num_matches: u8) -> Scratchcard {
+        let numbers = (0..num_matches).collect::<HashSet<u8>>();
+        Scratchcard {
+            winning_numbers: numbers.clone(),
+            our_numbers: numbers,
+            copies: 1,
+        }
+    }
+
+    #[test]
+    fn test_points_shift_agrees_with_points() {
+        for num_matches in 0..=63 {
+            let scratchcard = scratchcard_with_matches(num_matches);
+            assert_eq!(
+                scratchcard.points_shift(),
+                Some(scratchcard.points().unwrap()),
+                "{} matches",
+                num_matches
+            );
+        }
+
+        assert_eq!(scratchcard_with_matches(64).points_shift(), Some(1 << 63));
+        assert_eq!(scratchcard_with_matches(64).points().unwrap(), 1 << 63);
+
+        let scratchcard = scratchcard_with_matches(65);
+        assert_eq!(scratchcard.points_shift(), None);
+        assert!(scratchcard.points().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut scratchcard = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse::<Scratchcard>()
+            .unwrap();
+        scratchcard.copies = 3;
+
+        let json = serde_json::to_string(&scratchcard).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Scratchcard>(&json).unwrap(),
+            scratchcard
+        );
+    }
+
+    #[test]
+    fn test_parse_with_winning_numbers() {
+        let winning_numbers = parse_winning_numbers("1 2\n3\n").unwrap();
+        assert_eq!(
+            winning_numbers,
+            [1, 2, 3].into_iter().collect::<HashSet<u8>>()
+        );
+
+        let scratchcard =
+            Scratchcard::parse_with_winning_numbers("Card 1: 2 3 9", &winning_numbers).unwrap();
+        assert_eq!(scratchcard.num_matches(), 2);
+        assert_eq!(scratchcard.points().unwrap(), 2);
+
+        assert!(
+            Scratchcard::parse_with_winning_numbers("Card 1: 1 | 2 3 9", &winning_numbers).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_trailing_carriage_return() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+        assert_eq!(
+            format!("{}\r", input).parse::<Scratchcard>().unwrap(),
+            input.parse::<Scratchcard>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient_without_separator() {
+        let scratchcard = Scratchcard::parse_lenient("Card 7: 1 2 3").unwrap();
+        assert_eq!(
+            scratchcard,
+            Scratchcard {
+                winning_numbers: [1, 2, 3].into_iter().collect(),
+                our_numbers: HashSet::new(),
+                copies: 1,
+            }
+        );
+        assert_eq!(scratchcard.num_matches(), 0);
+        assert_eq!(scratchcard.points().unwrap(), 0);
+
+        assert!("Card 7: 1 2 3".parse::<Scratchcard>().is_err());
+    }
+
+    #[test]
+    fn test_from_sets() {
+        let scratchcard =
+            Scratchcard::from_sets([41, 48, 83, 86, 17], [83, 86, 6, 31, 17, 9, 48, 53]);
+        assert_eq!(
+            scratchcard,
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+                .parse::<Scratchcard>()
+                .unwrap()
+        );
+        assert_eq!(scratchcard.num_matches(), 4);
+        assert_eq!(scratchcard.points().unwrap(), 8);
+
+        // Duplicates only count once
+        let scratchcard = Scratchcard::from_sets([1, 1, 2], [1, 1]);
+        assert_eq!(scratchcard.num_matches(), 1);
+        assert_eq!(scratchcard.points().unwrap(), 1);
+        assert_eq!(scratchcard.copies, 1);
+    }
+
+    #[test]
+    fn test_parse_lenient_normal_card() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+        assert_eq!(
+            Scratchcard::parse_lenient(input).unwrap(),
+            input.parse::<Scratchcard>().unwrap()
+        );
+        assert!(Scratchcard::parse_lenient("Card 1: 1 2 |").is_err());
+        assert!(Scratchcard::parse_lenient("Card 1:").is_err());
+    }
 }
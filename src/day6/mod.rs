@@ -0,0 +1,336 @@
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{ensure, Context, Result};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use tracing::debug;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (`Time:` and `Distance:` lines).
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Product of the number of ways to win each race
+    pub part1: u64,
+    /// Number of ways to win the single long race
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!(
+        "(Part 1) Product of the number of ways to win each race: {}",
+        answers.part1
+    );
+    info!(
+        "(Part 2) Number of ways to win the single long race: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day6::{solve, Answers};
+/// let answers = solve("Time: 7\nDistance: 9\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 4, part2: 4 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let races = parse_races(input)?;
+    let product_of_ways: u64 = races
+        .iter()
+        .map(|race| {
+            let ways = race.ways_to_win();
+            debug!("{:?} -> {} ways to win", race, ways);
+            ways
+        })
+        .product();
+
+    let single_race = parse_single_race(input)?;
+
+    Ok(Answers {
+        part1: product_of_ways,
+        part2: single_race.ways_to_win(),
+    })
+}
+
+/// A single boat race: How long it lasts, and the best distance anyone has achieved so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    /// Total duration of the race, in milliseconds
+    pub time: u64,
+    /// The distance we need to beat, in millimeters
+    pub record_distance: u64,
+}
+
+impl Race {
+    /// Returns the distance travelled when the button is held for `hold_time` milliseconds.
+    /// The boat gains one millimeter per millisecond of speed for every millisecond the button
+    /// is held, and then travels at that speed for the remaining time.
+    ///
+    /// This is a `u128`, because the distance overflows a `u64` for race times above about
+    /// 8.6e9 milliseconds, which the concatenated numbers of part 2 can easily reach.
+    pub fn distance_for_hold_time(&self, hold_time: u64) -> u128 {
+        u128::from(hold_time) * u128::from(self.time - hold_time)
+    }
+
+    fn beats_record(&self, hold_time: u64) -> bool {
+        hold_time <= self.time
+            && self.distance_for_hold_time(hold_time) > u128::from(self.record_distance)
+    }
+
+    /// Counts the number of hold times `t` where `t * (time - t) > record_distance`.
+    ///
+    /// Instead of trying every hold time, this solves the quadratic
+    /// `t^2 - time * t + record_distance = 0` and counts the integers strictly between
+    /// its two roots. Because the floating point roots may be slightly off for large inputs,
+    /// the rounded bounds are then corrected using exact integer arithmetic. A hold time that
+    /// exactly ties the record does not count as a win.
+    pub fn ways_to_win(&self) -> u64 {
+        let time = self.time as f64;
+        let discriminant = time * time - 4.0 * self.record_distance as f64;
+        if discriminant < 0.0 {
+            return 0;
+        }
+        let sqrt_discriminant = discriminant.sqrt();
+
+        let mut lowest = ((time - sqrt_discriminant) / 2.0).floor().max(0.0) as u64;
+        let mut highest = ((time + sqrt_discriminant) / 2.0).ceil().min(time) as u64;
+
+        // Nudge the bounds until they are exactly the first and last winning hold times
+        while lowest <= self.time && !self.beats_record(lowest) {
+            lowest += 1;
+        }
+        while lowest > 0 && self.beats_record(lowest - 1) {
+            lowest -= 1;
+        }
+        while highest > 0 && !self.beats_record(highest) {
+            highest -= 1;
+        }
+        while self.beats_record(highest + 1) {
+            highest += 1;
+        }
+
+        if lowest > highest {
+            0
+        } else {
+            highest - lowest + 1
+        }
+    }
+}
+
+/// Parses the input as a list of races, as in part 1. Columns of the `Time:` line are paired up
+/// with the columns of the `Distance:` line.
+pub fn parse_races(input: &str) -> Result<Vec<Race>> {
+    let (times, distances) = split_time_and_distance_lines(input)?;
+
+    let times = parse_numbers(times).context("While parsing the `Time:` line")?;
+    let distances = parse_numbers(distances).context("While parsing the `Distance:` line")?;
+    ensure!(
+        times.len() == distances.len(),
+        "Found {} times, but {} distances",
+        times.len(),
+        distances.len()
+    );
+
+    Ok(times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, record_distance)| Race {
+            time,
+            record_distance,
+        })
+        .collect())
+}
+
+/// Parses the input as a single race, as in part 2. All the digits on each line are
+/// concatenated into a single number, ignoring the spaces in between.
+pub fn parse_single_race(input: &str) -> Result<Race> {
+    let (times, distances) = split_time_and_distance_lines(input)?;
+
+    Ok(Race {
+        time: parse_concatenated_number(times).context("While parsing the `Time:` line")?,
+        record_distance: parse_concatenated_number(distances)
+            .context("While parsing the `Distance:` line")?,
+    })
+}
+
+/// Returns the contents of the `Time:` and `Distance:` lines, without their prefixes.
+fn split_time_and_distance_lines(input: &str) -> Result<(&str, &str)> {
+    let mut lines = input.lines().filter(|line| !line.is_empty());
+
+    let times = lines
+        .next()
+        .and_then(|line| line.strip_prefix("Time:"))
+        .context("Expected the first line to start with `Time:`")?;
+    let distances = lines
+        .next()
+        .and_then(|line| line.strip_prefix("Distance:"))
+        .context("Expected the second line to start with `Distance:`")?;
+
+    Ok((times, distances))
+}
+
+fn parse_numbers(input: &str) -> Result<Vec<u64>> {
+    input
+        .split_whitespace()
+        .map(|number| {
+            number
+                .parse::<u64>()
+                .with_context(|| format!("Invalid number `{}`", number))
+        })
+        .collect()
+}
+
+fn parse_concatenated_number(input: &str) -> Result<u64> {
+    let digits = input.split_whitespace().collect::<String>();
+    digits
+        .parse::<u64>()
+        .with_context(|| format!("Invalid number `{}`", digits))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+    #[test]
+    fn test_parse_races() {
+        assert_eq!(
+            parse_races(EXAMPLE_INPUT).unwrap(),
+            vec![
+                Race {
+                    time: 7,
+                    record_distance: 9
+                },
+                Race {
+                    time: 15,
+                    record_distance: 40
+                },
+                Race {
+                    time: 30,
+                    record_distance: 200
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_single_race() {
+        assert_eq!(
+            parse_single_race(EXAMPLE_INPUT).unwrap(),
+            Race {
+                time: 71530,
+                record_distance: 940200
+            }
+        );
+    }
+
+    #[test]
+    fn test_ways_to_win_example_data() {
+        let ways = parse_races(EXAMPLE_INPUT)
+            .unwrap()
+            .iter()
+            .map(Race::ways_to_win)
+            .collect::<Vec<_>>();
+        assert_eq!(ways, vec![4, 8, 9]);
+        assert_eq!(ways.iter().product::<u64>(), 288);
+
+        assert_eq!(
+            parse_single_race(EXAMPLE_INPUT).unwrap().ways_to_win(),
+            71503
+        );
+    }
+
+    #[test]
+    fn test_ways_to_win_tied_record_does_not_count() {
+        // Holding for 10ms or 20ms travels exactly 200mm, which only ties the record
+        let race = Race {
+            time: 30,
+            record_distance: 200,
+        };
+        assert_eq!(race.distance_for_hold_time(10), 200);
+        assert_eq!(race.distance_for_hold_time(20), 200);
+        assert_eq!(race.ways_to_win(), 9);
+
+        // Only a single hold time (2ms) ties the record here, so nothing beats it
+        let race = Race {
+            time: 4,
+            record_distance: 4,
+        };
+        assert_eq!(race.ways_to_win(), 0);
+    }
+
+    #[test]
+    fn test_ways_to_win_large_concatenated_time() {
+        // The distances near half the time don't fit into a u64
+        let race =
+            parse_single_race("Time: 10000 000000\nDistance: 18000 000000 000000 000\n").unwrap();
+        assert_eq!(race.time, 10_000_000_000);
+        assert_eq!(
+            race.distance_for_hold_time(5_000_000_000),
+            25 * 10_u128.pow(18)
+        );
+        assert_eq!(race.ways_to_win(), 5_291_502_623);
+    }
+
+    #[test]
+    fn test_ways_to_win_matches_brute_force() {
+        for time in 0..60 {
+            for record_distance in 0..(time * time / 4 + 2) {
+                let race = Race {
+                    time,
+                    record_distance,
+                };
+                let brute_force = (0..=time).filter(|&t| race.beats_record(t)).count() as u64;
+                assert_eq!(race.ways_to_win(), brute_force, "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_races_mismatched_columns() {
+        assert!(parse_races("Time: 1 2\nDistance: 3").is_err());
+        assert!(parse_races("Distance: 3\nTime: 1").is_err());
+    }
+}
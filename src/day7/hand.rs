@@ -0,0 +1,211 @@
+use anyhow::{bail, ensure, Context, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Card labels, from weakest to strongest, under the standard rules.
+const CARD_ORDER: &str = "23456789TJQKA";
+/// Card labels, from weakest to strongest, when `J` is a joker.
+const CARD_ORDER_JOKERS: &str = "J23456789TQKA";
+
+const JOKER: char = 'J';
+
+/// Which set of rules to rank hands by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rules {
+    /// Part 1: `J` is a jack, ranked between `T` and `Q`.
+    Standard,
+    /// Part 2: `J` is a joker. It acts as whatever card makes the hand type strongest,
+    /// but is the weakest card when comparing hands of the same type.
+    Jokers,
+}
+
+/// The type of a hand, ordered from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+/// A hand of five cards, together with the amount that was bid on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: [char; 5],
+    pub bid: u64,
+}
+
+impl FromStr for Hand {
+    type Err = anyhow::Error;
+
+    /// Parses a string like `32T3K 765`
+    fn from_str(input: &str) -> Result<Hand> {
+        let (cards_str, bid_str) = input
+            .split_once(' ')
+            .with_context(|| format!("No space between cards and bid in `{}`", input))?;
+
+        let cards_vec = cards_str.chars().collect::<Vec<_>>();
+        let Ok(cards) = <[char; 5]>::try_from(cards_vec) else {
+            bail!(
+                "Hand `{}` does not consist of exactly five cards",
+                cards_str
+            );
+        };
+        for card in cards {
+            ensure!(
+                CARD_ORDER.contains(card),
+                "Card `{}` in hand `{}` is not valid",
+                card,
+                cards_str
+            );
+        }
+
+        let bid = bid_str
+            .parse::<u64>()
+            .with_context(|| format!("Bid `{}` in `{}` is not valid", bid_str, input))?;
+
+        Ok(Hand { cards, bid })
+    }
+}
+
+impl Hand {
+    /// Determines the type of this hand by counting how often each label occurs.
+    pub fn hand_type(&self, rules: Rules) -> HandType {
+        let mut label_counts: HashMap<char, u8> = HashMap::new();
+        let mut num_jokers = 0;
+        for card in self.cards {
+            if rules == Rules::Jokers && card == JOKER {
+                num_jokers += 1;
+            } else {
+                *label_counts.entry(card).or_default() += 1;
+            }
+        }
+
+        let mut counts = label_counts.into_values().collect::<Vec<u8>>();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        // Jokers always do the most good by joining the largest group of equal cards.
+        // If the hand consists only of jokers, they form a group of their own.
+        match counts.first_mut() {
+            Some(largest) => *largest += num_jokers,
+            None => counts.push(num_jokers),
+        }
+
+        match counts.as_slice() {
+            [5] => HandType::FiveOfAKind,
+            [4, ..] => HandType::FourOfAKind,
+            [3, 2] => HandType::FullHouse,
+            [3, ..] => HandType::ThreeOfAKind,
+            [2, 2, ..] => HandType::TwoPair,
+            [2, ..] => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
+
+    /// Key that hands are ranked by: First the hand type, then the strength of each card,
+    /// from first to last.
+    pub fn rank_key(&self, rules: Rules) -> (HandType, [usize; 5]) {
+        let card_order = match rules {
+            Rules::Standard => CARD_ORDER,
+            Rules::Jokers => CARD_ORDER_JOKERS,
+        };
+        // Labels were validated while parsing, so they are always found
+        let strengths = self.cards.map(|card| card_order.find(card).unwrap());
+
+        (self.hand_type(rules), strengths)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hand(cards: &str) -> Hand {
+        format!("{} 1", cards).parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_hand() {
+        assert_eq!(
+            "32T3K 765".parse::<Hand>().unwrap(),
+            Hand {
+                cards: ['3', '2', 'T', '3', 'K'],
+                bid: 765
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_hand_bad_input() {
+        assert!("32T3 765".parse::<Hand>().is_err());
+        assert!("32T3KA 765".parse::<Hand>().is_err());
+        assert!("32T3X 765".parse::<Hand>().is_err());
+        assert!("32T3K".parse::<Hand>().is_err());
+        assert!("32T3K -1".parse::<Hand>().is_err());
+    }
+
+    #[test]
+    fn test_hand_type_standard() {
+        assert_eq!(
+            hand("AAAAA").hand_type(Rules::Standard),
+            HandType::FiveOfAKind
+        );
+        assert_eq!(
+            hand("AA8AA").hand_type(Rules::Standard),
+            HandType::FourOfAKind
+        );
+        assert_eq!(
+            hand("23332").hand_type(Rules::Standard),
+            HandType::FullHouse
+        );
+        assert_eq!(
+            hand("TTT98").hand_type(Rules::Standard),
+            HandType::ThreeOfAKind
+        );
+        assert_eq!(hand("23432").hand_type(Rules::Standard), HandType::TwoPair);
+        assert_eq!(hand("A23A4").hand_type(Rules::Standard), HandType::OnePair);
+        assert_eq!(hand("23456").hand_type(Rules::Standard), HandType::HighCard);
+        assert_eq!(hand("KTJJT").hand_type(Rules::Standard), HandType::TwoPair);
+    }
+
+    #[test]
+    fn test_hand_type_jokers() {
+        assert_eq!(
+            hand("QJJQ2").hand_type(Rules::Jokers),
+            HandType::FourOfAKind
+        );
+        assert_eq!(
+            hand("KTJJT").hand_type(Rules::Jokers),
+            HandType::FourOfAKind
+        );
+        assert_eq!(
+            hand("T55J5").hand_type(Rules::Jokers),
+            HandType::FourOfAKind
+        );
+        assert_eq!(
+            hand("JJJJJ").hand_type(Rules::Jokers),
+            HandType::FiveOfAKind
+        );
+        assert_eq!(
+            hand("2JJJJ").hand_type(Rules::Jokers),
+            HandType::FiveOfAKind
+        );
+        assert_eq!(hand("2233J").hand_type(Rules::Jokers), HandType::FullHouse);
+        assert_eq!(hand("2345J").hand_type(Rules::Jokers), HandType::OnePair);
+        assert_eq!(hand("32T3K").hand_type(Rules::Jokers), HandType::OnePair);
+    }
+
+    #[test]
+    fn test_rank_key_tie_break() {
+        // Same type: compared card by card, first card wins
+        assert!(hand("33332").rank_key(Rules::Standard) > hand("2AAAA").rank_key(Rules::Standard));
+        assert!(hand("77888").rank_key(Rules::Standard) > hand("77788").rank_key(Rules::Standard));
+        // Jokers are the weakest card in tie-breaks, even though they strengthen the type
+        assert!(hand("JKKK2").rank_key(Rules::Jokers) < hand("QQQQ2").rank_key(Rules::Jokers));
+        assert!(hand("JKKK2").rank_key(Rules::Standard) < hand("QQQQ2").rank_key(Rules::Standard));
+        assert!(hand("2JJJJ").rank_key(Rules::Jokers) > hand("JJJJJ").rank_key(Rules::Jokers));
+    }
+}
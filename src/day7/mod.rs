@@ -0,0 +1,132 @@
+mod hand;
+
+pub use hand::{Hand, HandType, Rules};
+
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{Context, Result};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (one hand and its bid per line).
+    /// Empty lines are ignored.
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Total winnings under the standard rules
+    pub part1: u64,
+    /// Total winnings when `J` is a joker
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        DayResult::new(answers.part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!("(Part 1) Total winnings: {}", answers.part1);
+    info!("(Part 2) Total winnings with jokers: {}", answers.part2);
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day7::{solve, Answers};
+/// let answers = solve("32T3K 765\nKK677 28\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 821, part2: 821 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let hands = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_idx, line)| {
+            line.parse::<Hand>().with_context(|| {
+                format!("While trying to parse line {} (`{}`)", line_idx + 1, line)
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Answers {
+        part1: total_winnings(&hands, Rules::Standard),
+        part2: total_winnings(&hands, Rules::Jokers),
+    })
+}
+
+/// Ranks all hands from weakest (rank 1) to strongest, according to the given `rules`,
+/// and sums up `rank * bid` for every hand.
+pub fn total_winnings(hands: &[Hand], rules: Rules) -> u64 {
+    let mut ranked = hands.iter().collect::<Vec<_>>();
+    ranked.sort_by_cached_key(|hand| hand.rank_key(rules));
+
+    ranked
+        .into_iter()
+        .zip(1..)
+        .map(|(hand, rank)| rank * hand.bid)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    fn example_hands() -> Vec<Hand> {
+        EXAMPLE_INPUT
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_total_winnings_example_data() {
+        let hands = example_hands();
+        assert_eq!(total_winnings(&hands, Rules::Standard), 6440);
+        assert_eq!(total_winnings(&hands, Rules::Jokers), 5905);
+    }
+
+    #[test]
+    fn test_total_winnings_empty() {
+        assert_eq!(total_winnings(&[], Rules::Standard), 0);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::answer::{Answer, DayResult};
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{bail, ensure, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::debug;
+#[cfg(feature = "cli")]
+use tracing::{info, warn};
+
+const START_NODE: &str = "AAA";
+const END_NODE: &str = "ZZZ";
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (instructions, followed by the network of nodes).
+    /// Empty lines are ignored.
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Steps from `AAA` to `ZZZ`, or `None` if the network has no `AAA` node
+    /// (like the part 2 example)
+    pub part1: Option<u64>,
+    /// Steps until all ghosts are on a node ending with `Z`
+    pub part2: u64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        let part1 = answers.part1.map_or(Answer::from("-"), Answer::Number);
+        DayResult::new(part1, answers.part2)
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    match answers.part1 {
+        Some(steps) => info!("(Part 1) Steps from {START_NODE} to {END_NODE}: {steps}"),
+        None => warn!("(Part 1) Skipped, the network has no {START_NODE} node"),
+    }
+    info!(
+        "(Part 2) Steps until all ghosts are on a node ending with Z: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day8::{solve, Answers};
+/// let answers = solve("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap();
+/// assert_eq!(answers, Answers { part1: Some(6), part2: 6 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let network = input.parse::<Network>()?;
+
+    let part1 = if network.nodes.contains_key(START_NODE) {
+        Some(network.steps_until(START_NODE, |node| node == END_NODE)?)
+    } else {
+        None
+    };
+
+    Ok(Answers {
+        part1,
+        part2: network.ghost_steps()?,
+    })
+}
+
+/// A single left/right instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Left,
+    Right,
+}
+
+/// The documents from the puzzle: A list of instructions, and the network of nodes
+/// (each node maps to its left and right neighbor).
+#[derive(Debug, PartialEq, Eq)]
+pub struct Network {
+    pub instructions: Vec<Instruction>,
+    pub nodes: HashMap<String, (String, String)>,
+}
+
+lazy_static! {
+    // Capture group 1 = Node name
+    // Capture group 2 = Left node
+    // Capture group 3 = Right node
+    static ref NODE_FORMAT: Regex = Regex::new(r"^(\w+) = \((\w+), (\w+)\)$").unwrap();
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    /// Parses the instruction line (like `LLR`), followed by node lines like `AAA = (BBB, CCC)`.
+    fn from_str(input: &str) -> Result<Network> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty());
+
+        let (_, instructions_str) = lines.next().context("Input is empty")?;
+        let instructions = instructions_str
+            .chars()
+            .map(|c| match c {
+                'L' => Ok(Instruction::Left),
+                'R' => Ok(Instruction::Right),
+                _ => bail!("Instruction `{}` is not valid", c),
+            })
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("While parsing instructions `{}`", instructions_str))?;
+        ensure!(!instructions.is_empty(), "There are no instructions");
+
+        let mut nodes = HashMap::new();
+        for (line_idx, line) in lines {
+            let captures = NODE_FORMAT.captures(line).with_context(|| {
+                format!("Line {} (`{}`) is not a valid node", line_idx + 1, line)
+            })?;
+            let name = captures[1].to_string();
+            let neighbors = (captures[2].to_string(), captures[3].to_string());
+            ensure!(
+                nodes.insert(name, neighbors).is_none(),
+                "Line {} (`{}`) defines node `{}` a second time",
+                line_idx + 1,
+                line,
+                &captures[1]
+            );
+        }
+
+        Ok(Network {
+            instructions,
+            nodes,
+        })
+    }
+}
+
+impl Network {
+    /// Follows the instructions (repeating them as often as necessary), starting at `start`,
+    /// and returns how many steps it took to reach a node for which `is_end` returns `true`.
+    ///
+    /// Returns an error if a node without a definition is reached, or if the walk loops
+    /// forever without reaching an end node.
+    pub fn steps_until<F>(&self, start: &str, is_end: F) -> Result<u64>
+    where
+        F: Fn(&str) -> bool,
+    {
+        // After visiting more (node, instruction index) combinations than there are, we must be
+        // going around in circles.
+        let max_steps = (self.nodes.len() * self.instructions.len()) as u64;
+
+        let mut current = start;
+        for (steps, instruction) in (0..).zip(self.instructions.iter().cycle()) {
+            if is_end(current) {
+                return Ok(steps);
+            }
+            ensure!(
+                steps <= max_steps,
+                "Starting at `{}`, no end node is ever reached",
+                start
+            );
+
+            let (left, right) = self
+                .nodes
+                .get(current)
+                .with_context(|| format!("Node `{}` is not defined", current))?;
+            current = match instruction {
+                Instruction::Left => left,
+                Instruction::Right => right,
+            };
+        }
+
+        unreachable!("cycle() over non-empty instructions never ends")
+    }
+
+    /// Starts a ghost on every node ending with `A` and moves them all simultaneously, returning
+    /// how many steps it takes until every ghost is on a node ending with `Z`.
+    ///
+    /// Simulating this directly takes far too long. Instead, this relies on a property of the
+    /// puzzle inputs: Each ghost walks in a loop, and reaches its end node after exactly the
+    /// length of that loop. So all ghosts line up after the least common multiple of the
+    /// individual step counts.
+    pub fn ghost_steps(&self) -> Result<u64> {
+        let mut start_nodes = self
+            .nodes
+            .keys()
+            .filter(|node| node.ends_with('A'))
+            .collect::<Vec<_>>();
+        ensure!(!start_nodes.is_empty(), "No node ends with `A`");
+        start_nodes.sort();
+
+        start_nodes.into_iter().try_fold(1, |steps_so_far, start| {
+            let steps = self.steps_until(start, |node| node.ends_with('Z'))?;
+            debug!("Ghost starting at {} arrives after {} steps", start, steps);
+            Ok(lcm(steps_so_far, steps))
+        })
+    }
+}
+
+/// Greatest common divisor, using the Euclidean algorithm.
+///
+/// ```
+/// # use advent_of_code_2023::day8::gcd;
+/// assert_eq!(gcd(12, 18), 6);
+/// assert_eq!(gcd(7, 0), 7);
+/// ```
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Least common multiple.
+///
+/// ```
+/// # use advent_of_code_2023::day8::lcm;
+/// assert_eq!(lcm(4, 6), 12);
+/// assert_eq!(lcm(0, 6), 0);
+/// ```
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT_1: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+
+    const EXAMPLE_INPUT_2: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+    const EXAMPLE_INPUT_PART_2: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    #[test]
+    fn test_parse_network() {
+        let network = EXAMPLE_INPUT_2.parse::<Network>().unwrap();
+        assert_eq!(
+            network.instructions,
+            vec![Instruction::Left, Instruction::Left, Instruction::Right]
+        );
+        assert_eq!(network.nodes.len(), 3);
+        assert_eq!(network.nodes["BBB"], ("AAA".to_string(), "ZZZ".to_string()));
+    }
+
+    #[test]
+    fn test_parse_network_bad_input() {
+        assert!("".parse::<Network>().is_err());
+        assert!("LXR\n\nAAA = (AAA, AAA)".parse::<Network>().is_err());
+        assert!("LR\n\nAAA = AAA, AAA".parse::<Network>().is_err());
+        assert!("LR\n\nAAA = (AAA, AAA)\nAAA = (BBB, BBB)"
+            .parse::<Network>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_steps_example_data() {
+        let network = EXAMPLE_INPUT_1.parse::<Network>().unwrap();
+        assert_eq!(network.steps_until("AAA", |node| node == "ZZZ").unwrap(), 2);
+
+        let network = EXAMPLE_INPUT_2.parse::<Network>().unwrap();
+        assert_eq!(network.steps_until("AAA", |node| node == "ZZZ").unwrap(), 6);
+    }
+
+    #[test]
+    fn test_steps_never_reaching_end() {
+        let network = EXAMPLE_INPUT_1.parse::<Network>().unwrap();
+        assert!(network.steps_until("DDD", |node| node == "ZZZ").is_err());
+        assert!(network.steps_until("XYZ", |node| node == "ZZZ").is_err());
+    }
+
+    #[test]
+    fn test_ghost_steps_example_data() {
+        let network = EXAMPLE_INPUT_PART_2.parse::<Network>().unwrap();
+        assert_eq!(network.ghost_steps().unwrap(), 6);
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(2, 3), 1);
+        assert_eq!(gcd(18, 12), 6);
+        assert_eq!(lcm(2, 3), 6);
+        assert_eq!(lcm(12, 18), 36);
+        assert_eq!(lcm(1, 7), 7);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::answer::DayResult;
+#[cfg(feature = "cli")]
+use crate::input::{check_line_lengths, read_input};
+#[cfg(feature = "cli")]
+use crate::repeat::solve_repeatedly;
+use anyhow::{Context, Result};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use tracing::debug;
+#[cfg(feature = "cli")]
+use tracing::info;
+
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Input data from the puzzle (one sequence of space-separated numbers per line).
+    /// Empty lines are ignored.
+    #[arg(required_unless_present = "clipboard")]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub input_options: crate::input::InputOptions,
+    /// Fail on lines longer than this many bytes, instead of processing them.
+    /// Useful as a safety measure against pathological or untrusted inputs.
+    #[arg(long)]
+    pub max_line_length: Option<usize>,
+    /// Run the solution this many times on the same input (which is only read once),
+    /// e.g. for profiling. Only the final answers are logged.
+    #[arg(long, default_value = "1")]
+    pub repeat: usize,
+}
+
+/// Answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answers {
+    /// Sum of the extrapolated next values
+    pub part1: i64,
+    /// Sum of the extrapolated previous values
+    pub part2: i64,
+}
+
+impl From<Answers> for DayResult {
+    fn from(answers: Answers) -> DayResult {
+        // The extrapolated values can be negative, which `Answer::Number` can't represent
+        DayResult::new(answers.part1.to_string(), answers.part2.to_string())
+    }
+}
+
+#[cfg(feature = "cli")]
+pub fn run(args: Args) -> Result<()> {
+    let input = read_input(args.input.as_deref(), &args.input_options)?;
+    check_line_lengths(&input, args.max_line_length)?;
+
+    let answers = solve_repeatedly(&input, args.repeat, solve)?;
+    info!(
+        "(Part 1) Sum of extrapolated next values: {}",
+        answers.part1
+    );
+    info!(
+        "(Part 2) Sum of extrapolated previous values: {}",
+        answers.part2
+    );
+
+    Ok(())
+}
+
+/// Solves both parts of the puzzle for the given input, without touching the filesystem.
+///
+/// # Example
+///
+/// ```
+/// # use advent_of_code_2023::day9::{solve, Answers};
+/// let answers = solve("0 3 6 9 12 15\n").unwrap();
+/// assert_eq!(answers, Answers { part1: 18, part2: -3 });
+/// ```
+pub fn solve(input: &str) -> Result<Answers> {
+    let sequences = parse_sequences(input)?;
+
+    let mut sum_forward = 0;
+    let mut sum_backward = 0;
+    for sequence in &sequences {
+        let next = extrapolate_forward(sequence);
+        let previous = extrapolate_backward(sequence);
+        debug!("{:?}: previous = {}, next = {}", sequence, previous, next);
+
+        sum_forward += next;
+        sum_backward += previous;
+    }
+
+    Ok(Answers {
+        part1: sum_forward,
+        part2: sum_backward,
+    })
+}
+
+/// Parses every non-empty line into a sequence of numbers.
+pub fn parse_sequences(input: &str) -> Result<Vec<Vec<i64>>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_idx, line)| {
+            line.split_whitespace()
+                .map(|number| {
+                    number
+                        .parse::<i64>()
+                        .with_context(|| format!("Invalid number `{}`", number))
+                })
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| {
+                    format!("While trying to parse line {} (`{}`)", line_idx + 1, line)
+                })
+        })
+        .collect()
+}
+
+/// Predicts the value following the given sequence.
+///
+/// The differences between successive values are taken repeatedly, until they are all zero.
+/// The next value is then the sum of the last value of every sequence of differences.
+/// An empty sequence extrapolates to 0, and a single value extrapolates to itself.
+///
+/// ```
+/// # use advent_of_code_2023::day9::extrapolate_forward;
+/// assert_eq!(extrapolate_forward(&[0, 3, 6, 9, 12, 15]), 18);
+/// ```
+pub fn extrapolate_forward(sequence: &[i64]) -> i64 {
+    let mut current = sequence.to_vec();
+    let mut next_value = 0;
+
+    while current.iter().any(|&value| value != 0) {
+        next_value += current[current.len() - 1];
+        current = differences(&current);
+    }
+
+    next_value
+}
+
+/// Predicts the value preceding the given sequence.
+///
+/// This works the same as [`extrapolate_forward`] on the reversed sequence.
+///
+/// ```
+/// # use advent_of_code_2023::day9::extrapolate_backward;
+/// assert_eq!(extrapolate_backward(&[10, 13, 16, 21, 30, 45]), 5);
+/// ```
+pub fn extrapolate_backward(sequence: &[i64]) -> i64 {
+    let reversed = sequence.iter().rev().copied().collect::<Vec<_>>();
+    extrapolate_forward(&reversed)
+}
+
+fn differences(sequence: &[i64]) -> Vec<i64> {
+    sequence
+        .windows(2)
+        .map(|window| window[1] - window[0])
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+    #[test]
+    fn test_example_data() {
+        let sequences = parse_sequences(EXAMPLE_INPUT).unwrap();
+        assert_eq!(sequences.len(), 3);
+
+        let forward = sequences
+            .iter()
+            .map(|sequence| extrapolate_forward(sequence))
+            .collect::<Vec<_>>();
+        assert_eq!(forward, vec![18, 28, 68]);
+        assert_eq!(forward.iter().sum::<i64>(), 114);
+
+        let backward = sequences
+            .iter()
+            .map(|sequence| extrapolate_backward(sequence))
+            .collect::<Vec<_>>();
+        assert_eq!(backward, vec![-3, 0, 5]);
+        assert_eq!(backward.iter().sum::<i64>(), 2);
+    }
+
+    #[test]
+    fn test_negative_numbers() {
+        let sequences = parse_sequences("-1 -4 -9 -16").unwrap();
+        assert_eq!(sequences, vec![vec![-1, -4, -9, -16]]);
+        assert_eq!(extrapolate_forward(&sequences[0]), -25);
+        assert_eq!(extrapolate_backward(&sequences[0]), 0);
+    }
+
+    #[test]
+    fn test_short_sequences() {
+        assert_eq!(extrapolate_forward(&[]), 0);
+        assert_eq!(extrapolate_backward(&[]), 0);
+        assert_eq!(extrapolate_forward(&[7]), 7);
+        assert_eq!(extrapolate_backward(&[-7]), -7);
+        assert_eq!(extrapolate_forward(&[0]), 0);
+        assert_eq!(extrapolate_forward(&[2, 5]), 8);
+        assert_eq!(extrapolate_backward(&[2, 5]), -1);
+    }
+
+    #[test]
+    fn test_parse_bad_number() {
+        let err = parse_sequences("1 2 3\n4 x 6").unwrap_err();
+        assert_eq!(err.to_string(), "While trying to parse line 2 (`4 x 6`)");
+    }
+}
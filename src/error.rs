@@ -0,0 +1,154 @@
+//! A concrete error type for using the solvers as a library, for callers who want to `match` on
+//! what went wrong instead of handling an [`anyhow::Error`].
+//!
+//! The solvers themselves keep returning [`anyhow::Result`], so that enabling this feature does
+//! not change their signatures for anyone else. [`solve_with`] wraps any of them instead.
+
+use crate::parse::LineContext;
+use std::io;
+use std::num::TryFromIntError;
+
+/// Why solving a day failed.
+///
+/// Only the days that attach a [`LineContext`] to the line that failed to parse report it as
+/// [`AocError::Parse`]: days 2, 4, 13, 18, 19 and 20. The parse errors of the other days (e.g.
+/// grids that are not rectangular) end up in [`AocError::Other`].
+#[derive(Debug, thiserror::Error)]
+pub enum AocError {
+    /// Reading the input failed
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// A line of the input could not be parsed
+    #[error("Day {day}: failed to parse line {line}: {msg}")]
+    Parse {
+        day: u8,
+        /// 1-based line number
+        line: usize,
+        /// Why the line could not be parsed
+        msg: String,
+    },
+    /// A number did not fit into the integer type it had to be converted to
+    #[error("Overflow: {0}")]
+    Overflow(#[from] TryFromIntError),
+    /// Any other error, e.g. an input that parses, but has no solution
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl AocError {
+    /// Sorts an error returned by day `day`'s solver into the matching variant, by looking for an
+    /// I/O error, a [`LineContext`] or a [`TryFromIntError`] (in that order) in its chain.
+    pub fn from_day(day: u8, error: anyhow::Error) -> AocError {
+        if error.downcast_ref::<io::Error>().is_some() {
+            return match error.downcast::<io::Error>() {
+                Ok(io_error) => AocError::Io(io_error),
+                Err(error) => AocError::Other(error),
+            };
+        }
+        if let Some(context) = error.downcast_ref::<LineContext>() {
+            let context_str = context.to_string();
+            let msg = error
+                .chain()
+                .skip_while(|cause| cause.to_string() != context_str)
+                .skip(1)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(": ");
+            return AocError::Parse {
+                day,
+                line: context.line,
+                msg,
+            };
+        }
+        if let Some(&overflow) = error.downcast_ref::<TryFromIntError>() {
+            return AocError::Overflow(overflow);
+        }
+        AocError::Other(error)
+    }
+}
+
+/// Runs one of the days' `solve` functions, converting its error with [`AocError::from_day`].
+///
+/// ```
+/// # use advent_of_code_2023::day2;
+/// # use advent_of_code_2023::error::{solve_with, AocError};
+/// match solve_with(2, "Game 1: 3 purple\n", day2::solve) {
+///     Err(AocError::Parse { day, line, .. }) => assert_eq!((day, line), (2, 1)),
+///     other => panic!("Expected a parse error, got {:?}", other),
+/// }
+/// ```
+pub fn solve_with<T, F>(day: u8, input: &str, solve: F) -> Result<T, AocError>
+where
+    F: FnOnce(&str) -> anyhow::Result<T>,
+{
+    solve(input).map_err(|error| AocError::from_day(day, error))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{day2, day4};
+    use anyhow::Context;
+
+    #[test]
+    fn test_parse_error_from_bad_day2_line() {
+        let input = "\
+Game 1: 3 blue, 4 red
+Game 2: 3 blue, 4 purple
+Game 3: 1 green
+";
+        match solve_with(2, input, day2::solve) {
+            Err(AocError::Parse { day, line, msg }) => {
+                assert_eq!(day, 2);
+                assert_eq!(line, 2);
+                assert!(msg.contains("purple"), "{}", msg);
+            }
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+        assert!(solve_with(2, &input.replace("purple", "red"), day2::solve).is_ok());
+    }
+
+    fn assert_parse_error_on_line<T: std::fmt::Debug>(result: Result<T, AocError>, line: usize) {
+        match result {
+            Err(AocError::Parse { line: actual, .. }) => assert_eq!(actual, line),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_from_line_parsing_days() {
+        use crate::{day13, day18, day19, day20};
+
+        assert_parse_error_on_line(solve_with(13, "#.\n#x\n", day13::solve), 2);
+        let input = "R 6 (#70c710)\nX 6 (#70c710)\n";
+        assert_parse_error_on_line(solve_with(18, input, day18::solve), 2);
+        assert_parse_error_on_line(solve_with(19, "in{A}\n\n{x=1}\n", day19::solve), 3);
+        let input = "broadcaster -> a\n$a -> b\n";
+        assert_parse_error_on_line(solve_with(20, input, day20::solve), 2);
+    }
+
+    #[test]
+    fn test_other_errors() {
+        let error = u8::try_from(256_u64)
+            .context("While converting")
+            .unwrap_err();
+        assert!(matches!(
+            AocError::from_day(1, error),
+            AocError::Overflow(_)
+        ));
+
+        let error = anyhow::Error::new(io::Error::new(io::ErrorKind::NotFound, "missing"));
+        assert!(matches!(AocError::from_day(1, error), AocError::Io(_)));
+
+        // Day 4 fails on too many matches to score, which is not a parse error
+        let numbers = (1..=65)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let input = format!("Card 1: {numbers} | {numbers}\n");
+        assert!(matches!(
+            solve_with(4, &input, day4::solve),
+            Err(AocError::Other(_))
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::{bail, Context, Result};
+use reqwest::StatusCode;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Name of the environment variable that holds the adventofcode.com session cookie.
+const SESSION_ENV_VAR: &str = "AOC_SESSION";
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Day of the puzzle whose input should be downloaded.
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+    /// File to write the puzzle input to.
+    pub out: PathBuf,
+    /// Value of the `session` cookie from adventofcode.com.
+    /// Defaults to the `AOC_SESSION` environment variable.
+    #[arg(long)]
+    pub session: Option<String>,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let session = resolve_session(args.session, env::var(SESSION_ENV_VAR).ok())?;
+    let input = download_input(args.day, &session)?;
+
+    fs::write(&args.out, &input)
+        .with_context(|| format!("While trying to write file {}", args.out.display()))?;
+    info!(
+        "Wrote the input for day {} ({} bytes) to {}",
+        args.day,
+        input.len(),
+        args.out.display()
+    );
+
+    Ok(())
+}
+
+/// Picks the session cookie given via `--session`, falling back to the one from the environment.
+fn resolve_session(from_args: Option<String>, from_env: Option<String>) -> Result<String> {
+    match from_args.or(from_env) {
+        Some(session) if !session.trim().is_empty() => Ok(session.trim().to_string()),
+        _ => bail!(
+            "No session cookie given. Log in to adventofcode.com, copy the value of the `session` \
+            cookie, and pass it with `--session` or the `{}` environment variable",
+            SESSION_ENV_VAR
+        ),
+    }
+}
+
+/// Downloads the puzzle input for the given day of 2023.
+fn download_input(day: u8, session: &str) -> Result<String> {
+    let url = format!("https://adventofcode.com/2023/day/{}/input", day);
+
+    let response = reqwest::blocking::Client::new()
+        .get(&url)
+        .header(reqwest::header::COOKIE, format!("session={}", session))
+        .header(
+            reqwest::header::USER_AGENT,
+            concat!("advent-of-code-2023/", env!("CARGO_PKG_VERSION")),
+        )
+        .send()
+        .with_context(|| format!("While trying to download {}", url))?;
+
+    match response.status() {
+        StatusCode::OK => {}
+        StatusCode::NOT_FOUND => bail!(
+            "While trying to download {}: Day {} is not unlocked yet (HTTP 404)",
+            url,
+            day
+        ),
+        StatusCode::BAD_REQUEST => bail!(
+            "While trying to download {}: The session cookie was rejected, \
+            it is probably invalid or expired (HTTP 400)",
+            url
+        ),
+        status => bail!(
+            "While trying to download {}: Unexpected response status {}",
+            url,
+            status
+        ),
+    }
+
+    response
+        .text()
+        .with_context(|| format!("While trying to read the response from {}", url))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve_session_missing() {
+        let err = resolve_session(None, None).unwrap_err().to_string();
+        assert!(err.contains("--session"), "{}", err);
+        assert!(err.contains("AOC_SESSION"), "{}", err);
+
+        assert!(resolve_session(None, Some("  ".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_resolve_session_prefers_args() {
+        assert_eq!(
+            resolve_session(Some("abc".to_string()), Some("def".to_string())).unwrap(),
+            "abc"
+        );
+        assert_eq!(
+            resolve_session(None, Some("def\n".to_string())).unwrap(),
+            "def"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::{ensure, Result};
+use std::str::FromStr;
+
+/// Offsets (row, column) of the four orthogonal neighbors of a cell.
+const NEIGHBOR_OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets (row, column) of all eight neighbors of a cell, including diagonal ones.
+const NEIGHBOR_OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular, two-dimensional grid of cells, e.g. the characters of a puzzle input.
+///
+/// Rows and columns are 0-based. Cells are stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl FromStr for Grid<char> {
+    type Err = anyhow::Error;
+
+    /// Parses every line of the input as one row of the grid. Columns are counted in terms of
+    /// the `chars()` iterator, so multi-byte UTF-8 characters take up a single cell.
+    ///
+    /// All lines must be equally long, see [`Grid::from_str_padded`] for ragged input.
+    fn from_str(input: &str) -> Result<Grid<char>> {
+        let rows = input
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        if let Some(first_row) = rows.first() {
+            for (row_idx, row) in rows.iter().enumerate() {
+                ensure!(
+                    row.len() == first_row.len(),
+                    "Line {} is {} characters long, but line 1 is {} characters long",
+                    row_idx + 1,
+                    row.len(),
+                    first_row.len()
+                );
+            }
+        }
+
+        Ok(Grid::from_rows(rows, ' '))
+    }
+}
+
+impl Grid<char> {
+    /// Like the [`FromStr`] implementation, but instead of failing on lines of different lengths,
+    /// pads the shorter lines at the end with `fill` until they are as long as the longest line.
+    pub fn from_str_padded(input: &str, fill: char) -> Grid<char> {
+        let rows = input
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        Grid::from_rows(rows, fill)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Builds a grid from a list of rows. Rows shorter than the longest one are padded with `fill`.
+    pub fn from_rows(rows: Vec<Vec<T>>, fill: T) -> Grid<T> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, fill.clone());
+            cells.extend(row);
+        }
+
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the cell at the given position, or `None` if it is outside of the grid.
+    /// Takes signed coordinates, so that e.g. `row - 1` can be looked up without checking for
+    /// underflow first.
+    pub fn get(&self, row: isize, col: isize) -> Option<&T> {
+        let (row, col) = self.checked_position(row, col)?;
+        self.cells.get(row * self.width + col)
+    }
+
+    /// Mutable version of [`Grid::get`].
+    pub fn get_mut(&mut self, row: isize, col: isize) -> Option<&mut T> {
+        let (row, col) = self.checked_position(row, col)?;
+        self.cells.get_mut(row * self.width + col)
+    }
+
+    fn checked_position(&self, row: isize, col: isize) -> Option<(usize, usize)> {
+        let row = usize::try_from(row).ok()?;
+        let col = usize::try_from(col).ok()?;
+        (row < self.height && col < self.width).then_some((row, col))
+    }
+
+    /// Iterates over the rows of the grid, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks() panics on a chunk size of zero, which is the case for an empty grid
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterates over every cell as `(row, col, cell)`, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(idx, cell)| (idx / width, idx % width, cell))
+    }
+
+    /// Iterates over the (up to) four cells above, left of, right of and below the given cell,
+    /// as `(row, col, cell)`. Neighbors outside of the grid are skipped.
+    pub fn neighbors4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.neighbors(row, col, &NEIGHBOR_OFFSETS_4)
+    }
+
+    /// Like [`Grid::neighbors4`], but also includes the (up to) four diagonal neighbors.
+    pub fn neighbors8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.neighbors(row, col, &NEIGHBOR_OFFSETS_8)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        row: usize,
+        col: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize, &'a T)> {
+        offsets.iter().filter_map(move |&(row_offset, col_offset)| {
+            let neighbor_row = row as isize + row_offset;
+            let neighbor_col = col as isize + col_offset;
+            let cell = self.get(neighbor_row, neighbor_col)?;
+            Some((neighbor_row as usize, neighbor_col as usize, cell))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_grid() {
+        let grid = "abc\ndef".parse::<Grid<char>>().unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 0), Some(&'a'));
+        assert_eq!(grid.get(1, 2), Some(&'f'));
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&['a', 'b', 'c'][..], &['d', 'e', 'f'][..]]
+        );
+    }
+
+    #[test]
+    fn test_parse_grid_utf8() {
+        let grid = "߷.\n.߷".parse::<Grid<char>>().unwrap();
+        assert_eq!(grid.width(), 2);
+        assert_eq!(grid.get(1, 1), Some(&'߷'));
+    }
+
+    #[test]
+    fn test_parse_grid_ragged_lines() {
+        assert!("abc\nde".parse::<Grid<char>>().is_err());
+
+        let grid = Grid::from_str_padded("abc\n\nde", '.');
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 3);
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![
+                &['a', 'b', 'c'][..],
+                &['.', '.', '.'][..],
+                &['d', 'e', '.'][..]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_grid_empty() {
+        let grid = "".parse::<Grid<char>>().unwrap();
+        assert_eq!(grid.width(), 0);
+        assert_eq!(grid.height(), 0);
+        assert_eq!(grid.get(0, 0), None);
+        assert_eq!(grid.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_get_out_of_bounds() {
+        let grid = "abc\ndef".parse::<Grid<char>>().unwrap();
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get(0, -1), None);
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 3), None);
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut grid = "abc\ndef".parse::<Grid<char>>().unwrap();
+        *grid.get_mut(1, 0).unwrap() = 'x';
+        assert_eq!(grid.get(1, 0), Some(&'x'));
+        assert_eq!(grid.get_mut(5, 0), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let grid = "ab\ncd".parse::<Grid<char>>().unwrap();
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            vec![(0, 0, &'a'), (0, 1, &'b'), (1, 0, &'c'), (1, 1, &'d')]
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = "abc\ndef\nghi".parse::<Grid<char>>().unwrap();
+
+        let center_4 = grid
+            .neighbors4(1, 1)
+            .map(|(_, _, &c)| c)
+            .collect::<String>();
+        assert_eq!(center_4, "bdfh");
+        let center_8 = grid
+            .neighbors8(1, 1)
+            .map(|(_, _, &c)| c)
+            .collect::<String>();
+        assert_eq!(center_8, "abcdfghi");
+
+        assert_eq!(
+            grid.neighbors4(0, 0).collect::<Vec<_>>(),
+            vec![(0, 1, &'b'), (1, 0, &'d')]
+        );
+        assert_eq!(
+            grid.neighbors8(2, 2).collect::<Vec<_>>(),
+            vec![(1, 1, &'e'), (1, 2, &'f'), (2, 1, &'h')]
+        );
+    }
+}
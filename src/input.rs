@@ -0,0 +1,447 @@
+use anyhow::{bail, ensure, Context, Result};
+use std::fs;
+use std::io::{BufRead, BufReader, Cursor};
+use std::path::Path;
+use tracing::trace;
+
+/// Command line options for where the puzzle input comes from, shared by every day. Each day's
+/// `Args` flatten these next to their own (optional) `input` path.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct InputOptions {
+    /// Read the puzzle input from the system clipboard instead of a file
+    /// (requires the `clipboard` feature).
+    #[arg(long, conflicts_with = "input")]
+    pub clipboard: bool,
+    /// Decode the input file with this encoding (e.g. `windows-1252` or `latin1`, see the
+    /// [WHATWG labels](https://encoding.spec.whatwg.org/#names-and-labels)) instead of
+    /// expecting UTF-8. The clipboard is always read as text, so it is not affected.
+    #[cfg(feature = "encoding")]
+    #[arg(long, value_parser = encoding_for_label)]
+    pub input_encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+/// Reads the puzzle input into a string: From the clipboard with `--clipboard`, otherwise from
+/// the file at `path` (see [`read_input_file`]).
+///
+/// Fails if neither is given (which the command line arguments already rule out).
+pub fn read_input(path: Option<&Path>, options: &InputOptions) -> Result<String> {
+    read_input_with_clipboard(path, options, read_clipboard)
+}
+
+/// Reads a puzzle input file into a string, ignoring `--clipboard`.
+///
+/// If the path ends in `.gz`, the file is decompressed first (requires the `gzip` feature).
+/// Any other file is read as plain text, in UTF-8 unless another encoding is given with
+/// `--input-encoding` (requires the `encoding` feature, see [`read_input_with`]).
+#[cfg_attr(not(feature = "encoding"), allow(unused_variables))]
+pub fn read_input_file(path: &Path, options: &InputOptions) -> Result<String> {
+    #[cfg(feature = "encoding")]
+    return read_input_with(path, options.input_encoding);
+    #[cfg(not(feature = "encoding"))]
+    read_input_inner(path).with_context(|| format!("While trying to read file {}", path.display()))
+}
+
+/// Reads a puzzle input file into a string like [`read_input_file`], but decodes it with the
+/// given encoding instead of UTF-8 (if any).
+#[cfg(feature = "encoding")]
+pub fn read_input_with(
+    path: &Path,
+    encoding: Option<&'static encoding_rs::Encoding>,
+) -> Result<String> {
+    let input = match encoding {
+        Some(encoding) => read_encoded(path, encoding),
+        None => read_input_inner(path),
+    };
+    input.with_context(|| format!("While trying to read file {}", path.display()))
+}
+
+/// Opens the puzzle input for reading it line by line, instead of reading it into a string
+/// like [`read_input`]. `.gz` files are decompressed on the fly (requires the `gzip` feature).
+/// With `--clipboard`, the clipboard is read, like [`read_input`].
+pub fn open_input(path: Option<&Path>, options: &InputOptions) -> Result<Box<dyn BufRead>> {
+    if options.clipboard {
+        let input = read_clipboard().context(CLIPBOARD_ERROR_CONTEXT)?;
+        return Ok(Box::new(Cursor::new(input.into_bytes())));
+    }
+    let path = required_path(path)?;
+    #[cfg(feature = "encoding")]
+    if options.input_encoding.is_some() {
+        // Decoding needs the whole file, so it is read into a string first
+        let input = read_input_file(path, options)?;
+        return Ok(Box::new(Cursor::new(input.into_bytes())));
+    }
+    open_input_inner(path).with_context(|| format!("While trying to open file {}", path.display()))
+}
+
+/// Logs (at `trace` level) how many non-empty lines the input has, and how many empty lines
+/// are skipped, e.g. `Processed 1000 lines, skipped 3 empty`.
+/// Unexpected empty lines can be a sign of a truncated or badly pasted input.
+pub fn log_line_counts(input: &str) {
+    let (num_empty, num_non_empty) =
+        input
+            .lines()
+            .fold((0, 0), |(num_empty, num_non_empty), line| {
+                if line.is_empty() {
+                    (num_empty + 1, num_non_empty)
+                } else {
+                    (num_empty, num_non_empty + 1)
+                }
+            });
+    trace!(
+        "Processed {} lines, skipped {} empty",
+        num_non_empty,
+        num_empty
+    );
+}
+
+/// Fails if any line of the input is longer than `max_line_length` bytes, so that pathological
+/// inputs are rejected before any expensive work is done on them. `None` allows any length.
+pub fn check_line_lengths(input: &str, max_line_length: Option<usize>) -> Result<()> {
+    let Some(max_line_length) = max_line_length else {
+        return Ok(());
+    };
+    for (line_idx, line) in input.lines().enumerate() {
+        ensure!(
+            line.len() <= max_line_length,
+            "Line {} exceeds the maximum line length of {} bytes ({} bytes)",
+            line_idx + 1,
+            max_line_length,
+            line.len()
+        );
+    }
+    Ok(())
+}
+
+/// Decodes the raw bytes of an input file with the given encoding.
+/// Like [`fs::read_to_string`] for UTF-8, this fails on bytes that are invalid in the encoding,
+/// instead of replacing them.
+#[cfg(feature = "encoding")]
+pub fn decode_input(bytes: &[u8], encoding: &'static encoding_rs::Encoding) -> Result<String> {
+    let input = encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .with_context(|| format!("The input is not valid {}", encoding.name()))?;
+    Ok(input.into_owned())
+}
+
+#[cfg(feature = "encoding")]
+fn encoding_for_label(label: &str) -> Result<&'static encoding_rs::Encoding> {
+    encoding_rs::Encoding::for_label(label.as_bytes())
+        .with_context(|| format!("Unknown input encoding `{}`", label))
+}
+
+const CLIPBOARD_ERROR_CONTEXT: &str = "While trying to read the input from the clipboard";
+
+/// [`read_input`], but with the given function to read the clipboard, so that tests don't
+/// depend on the system clipboard.
+fn read_input_with_clipboard(
+    path: Option<&Path>,
+    options: &InputOptions,
+    read_clipboard: impl FnOnce() -> Result<String>,
+) -> Result<String> {
+    if options.clipboard {
+        return read_clipboard().context(CLIPBOARD_ERROR_CONTEXT);
+    }
+    read_input_file(required_path(path)?, options)
+}
+
+fn required_path(path: Option<&Path>) -> Result<&Path> {
+    match path {
+        Some(path) => Ok(path),
+        None => bail!("No input file given, and not reading from the clipboard"),
+    }
+}
+
+#[cfg(feature = "clipboard")]
+fn read_clipboard() -> Result<String> {
+    Ok(arboard::Clipboard::new()?.get_text()?)
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn read_clipboard() -> Result<String> {
+    anyhow::bail!("Reading the clipboard requires building with the `clipboard` feature")
+}
+
+#[cfg(feature = "encoding")]
+fn read_encoded(path: &Path, encoding: &'static encoding_rs::Encoding) -> Result<String> {
+    use std::io::Read;
+
+    let mut bytes = vec![];
+    open_input_inner(path)?.read_to_end(&mut bytes)?;
+    decode_input(&bytes, encoding)
+}
+
+fn read_input_inner(path: &Path) -> Result<String> {
+    if is_gzip_path(path) {
+        read_gzip(path)
+    } else {
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+fn open_input_inner(path: &Path) -> Result<Box<dyn BufRead>> {
+    let file = fs::File::open(path)?;
+    if is_gzip_path(path) {
+        open_gzip(file)
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+fn is_gzip_path(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "gz")
+}
+
+#[cfg(feature = "gzip")]
+fn read_gzip(path: &Path) -> Result<String> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let mut input = String::new();
+    GzDecoder::new(fs::File::open(path)?)
+        .read_to_string(&mut input)
+        .context("Failed to decompress the file")?;
+    Ok(input)
+}
+
+#[cfg(feature = "gzip")]
+fn open_gzip(file: fs::File) -> Result<Box<dyn BufRead>> {
+    Ok(Box::new(BufReader::new(flate2::read::GzDecoder::new(file))))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn open_gzip(_file: fs::File) -> Result<Box<dyn BufRead>> {
+    anyhow::bail!("Reading `.gz` files requires building with the `gzip` feature")
+}
+
+#[cfg(not(feature = "gzip"))]
+fn read_gzip(_path: &Path) -> Result<String> {
+    anyhow::bail!("Reading `.gz` files requires building with the `gzip` feature")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DAY1_EXAMPLE_INPUT: &str = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+
+    // Without the `encoding` feature, `clipboard` is the only field
+    #[allow(clippy::needless_update)]
+    fn clipboard_options() -> InputOptions {
+        InputOptions {
+            clipboard: true,
+            ..InputOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_read_input_plain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day1.txt");
+        fs::write(&path, DAY1_EXAMPLE_INPUT).unwrap();
+
+        assert_eq!(
+            read_input_file(&path, &InputOptions::default()).unwrap(),
+            DAY1_EXAMPLE_INPUT
+        );
+    }
+
+    #[test]
+    fn test_read_input_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let err =
+            read_input_file(&dir.path().join("day1.txt"), &InputOptions::default()).unwrap_err();
+        assert!(err.to_string().starts_with("While trying to read file"));
+    }
+
+    #[test]
+    fn test_open_input_plain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day1.txt");
+        fs::write(&path, DAY1_EXAMPLE_INPUT).unwrap();
+
+        let lines = open_input(Some(&path), &InputOptions::default())
+            .unwrap()
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, DAY1_EXAMPLE_INPUT.lines().collect::<Vec<_>>());
+
+        let Err(err) = open_input(
+            Some(&dir.path().join("missing.txt")),
+            &InputOptions::default(),
+        ) else {
+            panic!("Opening a missing file should fail");
+        };
+        assert!(err.to_string().starts_with("While trying to open file"));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_read_input_gzip() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let plain_path = dir.path().join("day1.txt");
+        fs::write(&plain_path, DAY1_EXAMPLE_INPUT).unwrap();
+
+        let gzip_path = dir.path().join("day1.txt.gz");
+        let mut encoder = GzEncoder::new(
+            fs::File::create(&gzip_path).unwrap(),
+            Compression::default(),
+        );
+        encoder.write_all(DAY1_EXAMPLE_INPUT.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let plain =
+            crate::day1::solve(&read_input_file(&plain_path, &InputOptions::default()).unwrap())
+                .unwrap();
+        let gzip =
+            crate::day1::solve(&read_input_file(&gzip_path, &InputOptions::default()).unwrap())
+                .unwrap();
+        assert_eq!(gzip, plain);
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_read_input_gzip_without_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day1.txt.gz");
+        fs::write(&path, [0x1f, 0x8b]).unwrap();
+
+        let err = format!(
+            "{:#}",
+            read_input_file(&path, &InputOptions::default()).unwrap_err()
+        );
+        assert!(err.contains("`gzip` feature"), "{}", err);
+    }
+
+    #[cfg(feature = "clipboard")]
+    #[test]
+    fn test_read_input_clipboard() {
+        let options = clipboard_options();
+        let clipboard = || Ok(DAY1_EXAMPLE_INPUT.to_owned());
+        let input = read_input_with_clipboard(None, &options, clipboard).unwrap();
+        let answers = crate::day1::solve(&input).unwrap();
+        assert_eq!(answers.part1, 142);
+
+        let err = read_input_with_clipboard(None, &options, || anyhow::bail!("No clipboard"))
+            .unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "While trying to read the input from the clipboard: No clipboard"
+        );
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_decode_input_windows_1252() {
+        // `Café 12€` followed by a line with a day 3 number next to a `×` symbol
+        let bytes = b"Caf\xe9 12\x80\n.7\xd7.\n";
+        let windows_1252 = encoding_for_label("windows-1252").unwrap();
+        let input = decode_input(bytes, windows_1252).unwrap();
+        assert_eq!(input, "Café 12€\n.7×.\n");
+
+        // The multi-byte `×` is a single symbol after decoding
+        let schematic = input
+            .lines()
+            .nth(1)
+            .unwrap()
+            .parse::<crate::day3::Schematic>();
+        assert_eq!(schematic.unwrap().part_numbers[0].part_number, 7);
+
+        // The same bytes are not valid UTF-8
+        let utf8 = encoding_for_label("utf-8").unwrap();
+        let err = decode_input(bytes, utf8).unwrap_err();
+        assert_eq!(err.to_string(), "The input is not valid UTF-8");
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_read_input_with_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.txt");
+        fs::write(&path, b"Caf\xe9\n").unwrap();
+
+        let options = InputOptions {
+            input_encoding: Some(encoding_for_label("latin1").unwrap()),
+            ..InputOptions::default()
+        };
+        assert_eq!(read_input(Some(&path), &options).unwrap(), "Café\n");
+        let mut lines = String::new();
+        open_input(Some(&path), &options)
+            .unwrap()
+            .read_to_string(&mut lines)
+            .unwrap();
+        assert_eq!(lines, "Café\n");
+
+        // Without an encoding, the same file is not valid UTF-8
+        assert!(read_input_with(&path, None).is_err());
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_encoding_for_label() {
+        // Labels are case-insensitive, and `latin1` is decoded as windows-1252 like browsers do
+        assert_eq!(
+            encoding_for_label("Latin1").unwrap(),
+            encoding_for_label("windows-1252").unwrap()
+        );
+        let err = encoding_for_label("klingon").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown input encoding `klingon`");
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    #[test]
+    fn test_read_input_clipboard_without_feature() {
+        let options = clipboard_options();
+        let err = format!("{:#}", read_input(None, &options).unwrap_err());
+        assert!(err.contains("`clipboard` feature"), "{}", err);
+    }
+
+    #[test]
+    fn test_read_input_from_file_named_like_the_clipboard() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clipboard:");
+        fs::write(&path, DAY1_EXAMPLE_INPUT).unwrap();
+
+        let options = InputOptions::default();
+        assert_eq!(
+            read_input(Some(&path), &options).unwrap(),
+            DAY1_EXAMPLE_INPUT
+        );
+        let err = read_input(None, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No input file given, and not reading from the clipboard"
+        );
+    }
+
+    #[test]
+    fn test_log_line_counts() {
+        let logs = crate::logging::capture_logs(|| {
+            log_line_counts("1abc2\n\npqr3stu8vwx\n\ntreb7uchet\n")
+        });
+        assert!(
+            logs.contains("Processed 3 lines, skipped 2 empty"),
+            "{}",
+            logs
+        );
+    }
+
+    #[test]
+    fn test_check_line_lengths() {
+        let huge_line = "1".repeat(1_000_000);
+        let err = check_line_lengths(&huge_line, Some(1000)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 1 exceeds the maximum line length of 1000 bytes (1000000 bytes)"
+        );
+
+        assert!(check_line_lengths(&huge_line, None).is_ok());
+        assert!(check_line_lengths(DAY1_EXAMPLE_INPUT, Some(11)).is_ok());
+        let err = check_line_lengths(DAY1_EXAMPLE_INPUT, Some(10)).unwrap_err();
+        assert!(err.to_string().starts_with("Line 2 exceeds"));
+    }
+}
@@ -1,7 +1,108 @@
+#[cfg(feature = "cli")]
+pub mod all;
+pub mod answer;
+#[cfg(feature = "cli")]
 mod args;
+pub mod build_info;
+pub mod checksum;
 pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
 pub mod day2;
+pub mod day20;
+pub mod day21;
 pub mod day3;
 pub mod day4;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+#[cfg(feature = "thiserror")]
+pub mod error;
+#[cfg(feature = "download")]
+pub mod fetch;
+pub mod grid;
+#[cfg(feature = "cli")]
+pub mod input;
+#[cfg(feature = "cli")]
+pub mod logging;
+pub mod parse;
+pub mod regex_profile;
+pub mod repeat;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+#[cfg(feature = "cli")]
 pub use args::*;
+
+/// Generates the [`DayCommand`] subcommand with one variant per day, each taking the day's
+/// `Args`, [`DayCommand::run`], which dispatches to the day's `run` function, and the `DAYS`
+/// table of every day's `solve` function for [`all`].
+///
+/// Every entry looks like `1 => Day1(day1)`: the day number, the variant and the module. The
+/// module needs `Args`, `run` and `solve`, and its `Answers` must convert into a
+/// [`DayResult`](answer::DayResult).
+macro_rules! register_days {
+    ($($day:literal => $variant:ident($module:ident)),* $(,)?) => {
+        /// The subcommands that run a single day.
+        #[cfg(feature = "cli")]
+        #[derive(clap::Subcommand, Debug)]
+        // Otherwise the doc comment ends up as the description of the whole binary
+        #[command(about = None, long_about = None)]
+        pub enum DayCommand {
+            $(
+                #[command(about = concat!("Run the two algorithms for day ", $day, "'s challenge"))]
+                $variant(crate::$module::Args),
+            )*
+        }
+
+        #[cfg(feature = "cli")]
+        impl DayCommand {
+            /// Runs the day with its arguments.
+            pub fn run(self) -> anyhow::Result<()> {
+                match self {
+                    $(DayCommand::$variant(args) => crate::$module::run(args),)*
+                }
+            }
+        }
+
+        /// Every implemented day, in order.
+        #[cfg(feature = "cli")]
+        pub(crate) const DAYS: [(u8, crate::all::DaySolver); [$($day),*].len()] = [
+            $(($day, |input| Ok(crate::$module::solve(input)?.into())),)*
+        ];
+    };
+}
+
+register_days! {
+    1 => Day1(day1),
+    2 => Day2(day2),
+    3 => Day3(day3),
+    4 => Day4(day4),
+    6 => Day6(day6),
+    7 => Day7(day7),
+    8 => Day8(day8),
+    9 => Day9(day9),
+    10 => Day10(day10),
+    11 => Day11(day11),
+    12 => Day12(day12),
+    13 => Day13(day13),
+    14 => Day14(day14),
+    15 => Day15(day15),
+    16 => Day16(day16),
+    17 => Day17(day17),
+    18 => Day18(day18),
+    19 => Day19(day19),
+    20 => Day20(day20),
+    21 => Day21(day21),
+}
@@ -0,0 +1,88 @@
+/// Installs the global `tracing` subscriber that prints the logs of the command line interface.
+///
+/// Does nothing if a global subscriber is already installed (e.g. when the days are run from
+/// tests or from another program), instead of panicking like `tracing_subscriber::fmt::init`.
+/// Library functions never install a subscriber themselves.
+pub fn init_logging() {
+    // The only possible error is that a global subscriber is already set
+    let _ = tracing_subscriber::fmt::try_init();
+}
+
+/// Runs `f` with a subscriber that captures every log (down to `trace` level) instead of printing
+/// it, and returns the captured logs, so that tests can inspect them.
+#[cfg(test)]
+pub(crate) fn capture_logs(f: impl FnOnce()) -> String {
+    let logs = CapturedLogs::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .with_writer(logs.clone())
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, f);
+
+    let logs = logs.0.lock().unwrap().clone();
+    String::from_utf8(logs).unwrap()
+}
+
+/// Collects everything a tracing subscriber writes.
+#[cfg(test)]
+#[derive(Clone, Default)]
+struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLogs {
+    type Writer = CapturedLogs;
+
+    fn make_writer(&'a self) -> CapturedLogs {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_run_twice_with_subscriber_installed() {
+        // Another test may have installed a global subscriber already, which is fine
+        let _ = tracing::subscriber::set_global_default(
+            tracing_subscriber::fmt().with_test_writer().finish(),
+        );
+        init_logging();
+        init_logging();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day1.txt");
+        fs::write(&path, "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n").unwrap();
+
+        for _ in 0..2 {
+            let args = crate::day1::Args {
+                input: Some(path.clone()),
+                input_options: Default::default(),
+                values: false,
+                spelled_out: false,
+                strict: false,
+                line_checksum: false,
+                assert_different: false,
+                assert_two_digit: false,
+                max_line_length: None,
+                stream: false,
+                repeat: 1,
+            };
+            crate::day1::run(args).unwrap();
+        }
+    }
+}
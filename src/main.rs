@@ -4,18 +4,11 @@ use advent_of_code_2023::{Args, Day};
 use clap::Parser;
 
 fn main() -> ExitCode {
-    tracing_subscriber::fmt::init();
+    advent_of_code_2023::logging::init_logging();
 
     let args = Args::parse();
 
-    let res = match args.day {
-        Day::Day1(day1_args) => advent_of_code_2023::day1::run(day1_args),
-        Day::Day2(day2_args) => advent_of_code_2023::day2::run(day2_args),
-        Day::Day3(day3_args) => advent_of_code_2023::day3::run(day3_args),
-        Day::Day4(day4_args) => advent_of_code_2023::day4::run(day4_args),
-    };
-
-    if let Err(err) = res {
+    if let Err(err) = run(args) {
         // {:#} shows the full error context, not just the outermost layer
         tracing::error!("{:#}", err);
         ExitCode::FAILURE
@@ -23,3 +16,13 @@ fn main() -> ExitCode {
         ExitCode::SUCCESS
     }
 }
+
+fn run(args: Args) -> anyhow::Result<()> {
+    match args.day {
+        Day::All(all_args) => advent_of_code_2023::all::run(all_args),
+        Day::Solve(day) => day.run(),
+        Day::BuildInfo => advent_of_code_2023::build_info::run(),
+        #[cfg(feature = "download")]
+        Day::Fetch(fetch_args) => advent_of_code_2023::fetch::run(fetch_args),
+    }
+}
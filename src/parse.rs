@@ -0,0 +1,221 @@
+#[cfg(feature = "cli")]
+use anyhow::bail;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "cli")]
+use tracing::error;
+
+/// The context attached to the error of a line that failed to parse, like
+/// "Failed to parse line 4: `three`". Can be found again with [`anyhow::Error::downcast_ref`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineContext {
+    /// 1-based line number
+    pub line: usize,
+    /// The whole line
+    pub content: String,
+}
+
+impl fmt::Display for LineContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to parse line {}: `{}`", self.line, self.content)
+    }
+}
+
+impl LineContext {
+    /// Context for line `line` (1-based) with the given content, for parsers that can't use
+    /// [`parse_lines_with`], e.g. because lines depend on each other.
+    pub fn new(line: usize, content: &str) -> LineContext {
+        LineContext {
+            line,
+            content: content.to_string(),
+        }
+    }
+}
+
+/// Iterates over the non-empty lines of `input` as `(line_no, line)`, where `line_no` is the
+/// 1-based line number in the whole input, i.e. empty lines are skipped, but still counted.
+///
+/// ```
+/// # use advent_of_code_2023::parse::numbered_nonempty_lines;
+/// let lines = numbered_nonempty_lines("a\n\nb\n").collect::<Vec<_>>();
+/// assert_eq!(lines, vec![(1, "a"), (3, "b")]);
+/// ```
+pub fn numbered_nonempty_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_idx, line)| (line_idx + 1, line))
+}
+
+/// Parses every non-empty line of `input` into a `T`, in order.
+///
+/// The first line that fails to parse is returned as the error, with its (1-based) line number
+/// attached as context.
+///
+/// ```
+/// # use advent_of_code_2023::parse::parse_lines;
+/// let numbers = parse_lines::<u8>("1\n\n2\n").unwrap();
+/// assert_eq!(numbers, vec![1, 2]);
+/// ```
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Into<anyhow::Error>,
+{
+    parse_lines_with(input, |line| line.parse::<T>().map_err(Into::into))
+}
+
+/// Like [`parse_lines`], but parses every line with `parse_line` instead of [`FromStr`],
+/// e.g. for more lenient parsers. The parsed lines may borrow from `input`.
+pub fn parse_lines_with<'a, T, F>(input: &'a str, parse_line: F) -> Result<Vec<T>>
+where
+    F: Fn(&'a str) -> Result<T>,
+{
+    numbered_nonempty_lines(input)
+        .map(|(line_no, line)| parse_line(line).with_context(|| LineContext::new(line_no, line)))
+        .collect()
+}
+
+/// Like [`parse_lines_with`], but doesn't stop at the first line that fails to parse.
+/// Returns either every parsed line, or the errors of every line that failed to parse (each with
+/// its line number attached as context), e.g. to fix all of them in one go.
+pub fn parse_lines_collecting_errors<T, F>(
+    input: &str,
+    parse_line: F,
+) -> std::result::Result<Vec<T>, Vec<anyhow::Error>>
+where
+    F: Fn(&str) -> Result<T>,
+{
+    let mut parsed = vec![];
+    let mut errors = vec![];
+    for (line_no, line) in numbered_nonempty_lines(input) {
+        match parse_line(line).with_context(|| LineContext::new(line_no, line)) {
+            Ok(value) => parsed.push(value),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Runs [`parse_lines_collecting_errors`] and logs every error, before failing with
+/// the number of lines that could not be parsed.
+#[cfg(feature = "cli")]
+pub fn report_parse_errors<T, F>(input: &str, parse_line: F) -> Result<Vec<T>>
+where
+    F: Fn(&str) -> Result<T>,
+{
+    match parse_lines_collecting_errors(input, parse_line) {
+        Ok(parsed) => Ok(parsed),
+        Err(errors) => {
+            for err in &errors {
+                error!("{:#}", err);
+            }
+            bail!("{} line(s) failed to parse", errors.len())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day2::Game;
+    use crate::day4::Scratchcard;
+
+    #[test]
+    fn test_parse_games() {
+        let input = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+";
+        let games = parse_lines::<Game>(input).unwrap();
+        assert_eq!(
+            games.iter().map(|game| game.id).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_parse_scratchcards() {
+        let input = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+";
+        let scratchcards = parse_lines::<Scratchcard>(input).unwrap();
+        assert_eq!(
+            scratchcards
+                .iter()
+                .map(Scratchcard::num_matches)
+                .collect::<Vec<_>>(),
+            vec![4, 2, 2]
+        );
+    }
+
+    #[test]
+    fn test_numbered_nonempty_lines_keeps_numbering() {
+        let input = "\n1\n\n\n4\r\n5\n\n";
+        assert_eq!(
+            numbered_nonempty_lines(input).collect::<Vec<_>>(),
+            vec![(2, "1"), (5, "4"), (6, "5")]
+        );
+        assert_eq!(numbered_nonempty_lines("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn test_error_mentions_line_number() {
+        let err = parse_lines::<u8>("1\n\n2\nthree\n4").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse line 4: `three`");
+        assert_eq!(
+            err.downcast_ref::<LineContext>(),
+            Some(&LineContext {
+                line: 4,
+                content: "three".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_collecting_errors_reports_every_line() {
+        let input = "\
+Game 1: 3 blue, 4 red
+Game 2: 3 purple
+
+Game three: 1 red
+Game 4: 1 green
+Game 5: 1 red; 0 blue
+";
+        let errors = parse_lines_collecting_errors(input, str::parse::<Game>).unwrap_err();
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "Failed to parse line 2: `Game 2: 3 purple`",
+                "Failed to parse line 4: `Game three: 1 red`",
+                "Failed to parse line 6: `Game 5: 1 red; 0 blue`",
+            ]
+        );
+
+        let games =
+            parse_lines_collecting_errors("Game 1: 3 blue, 4 red\n", str::parse::<Game>).unwrap();
+        assert_eq!(games.len(), 1);
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_report_parse_errors_counts_lines() {
+        let err = report_parse_errors("1\nx\n2\ny\nz", |line| Ok(line.parse::<u8>()?)).unwrap_err();
+        assert_eq!(err.to_string(), "3 line(s) failed to parse");
+        assert_eq!(
+            report_parse_errors("1\n\n2", |line| Ok(line.parse::<u8>()?)).unwrap(),
+            vec![1, 2]
+        );
+    }
+}
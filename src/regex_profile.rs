@@ -0,0 +1,70 @@
+//! Opt-in measurement of how much time the regex-based parsers (days 2 and 4) spend matching,
+//! for the hidden `--profile-regex` flag. While it is disabled, [`captures`] costs a single
+//! atomic load on top of the regex search itself.
+
+use regex::{Captures, Regex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SEARCHES: AtomicU64 = AtomicU64::new(0);
+static MATCHES: AtomicU64 = AtomicU64::new(0);
+static NANOS: AtomicU64 = AtomicU64::new(0);
+
+/// What was measured since [`enable`] was called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegexProfile {
+    /// Number of times a regex was run on an input
+    pub searches: u64,
+    /// Number of those searches that found a match
+    pub matches: u64,
+    /// Total time spent in the searches
+    pub time: Duration,
+}
+
+/// Starts measuring every call of [`captures`] (in every thread), from zero.
+pub fn enable() {
+    SEARCHES.store(0, Ordering::Relaxed);
+    MATCHES.store(0, Ordering::Relaxed);
+    NANOS.store(0, Ordering::Relaxed);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Everything measured since [`enable`] was called.
+pub fn profile() -> RegexProfile {
+    RegexProfile {
+        searches: SEARCHES.load(Ordering::Relaxed),
+        matches: MATCHES.load(Ordering::Relaxed),
+        time: Duration::from_nanos(NANOS.load(Ordering::Relaxed)),
+    }
+}
+
+/// Logs (at `debug` level) everything measured since [`enable`] was called.
+#[cfg(feature = "cli")]
+pub fn log_profile() {
+    let profile = profile();
+    tracing::debug!(
+        "Spent {:?} on {} regex searches ({} matches)",
+        profile.time,
+        profile.searches,
+        profile.matches
+    );
+}
+
+/// Same as [`Regex::captures`], but measured while profiling is enabled.
+pub fn captures<'h>(regex: &Regex, haystack: &'h str) -> Option<Captures<'h>> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return regex.captures(haystack);
+    }
+
+    let start = Instant::now();
+    let captures = regex.captures(haystack);
+    let nanos = start.elapsed().as_nanos() as u64;
+
+    SEARCHES.fetch_add(1, Ordering::Relaxed);
+    if captures.is_some() {
+        MATCHES.fetch_add(1, Ordering::Relaxed);
+    }
+    NANOS.fetch_add(nanos, Ordering::Relaxed);
+    captures
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use tracing::debug;
+
+/// Calls `solve` on the same `input` `repeat` times and returns the result of the last call,
+/// so that the computation can be profiled without re-reading the input file every time.
+///
+/// `solve` is always called at least once, even if `repeat` is 0. The first error is returned
+/// immediately.
+pub fn solve_repeatedly<T, F>(input: &str, repeat: usize, mut solve: F) -> Result<T>
+where
+    F: FnMut(&str) -> Result<T>,
+{
+    for iteration in 1..repeat {
+        solve(input)?;
+        debug!("Finished iteration {} of {}", iteration, repeat);
+    }
+    solve(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anyhow::bail;
+
+    #[test]
+    fn test_solve_repeatedly_calls_count() {
+        let input = "1abc2\npqr3stu8vwx";
+        let mut num_calls = 0;
+
+        let answers = solve_repeatedly(input, 3, |input| {
+            num_calls += 1;
+            crate::day1::solve(input)
+        })
+        .unwrap();
+
+        assert_eq!(num_calls, 3);
+        assert_eq!(answers, crate::day1::solve(input).unwrap());
+    }
+
+    #[test]
+    fn test_solve_repeatedly_at_least_once() {
+        let mut num_calls = 0;
+        solve_repeatedly("", 0, |_| {
+            num_calls += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(num_calls, 1);
+    }
+
+    #[test]
+    fn test_solve_repeatedly_stops_at_first_error() {
+        let mut num_calls = 0;
+        let result: Result<()> = solve_repeatedly("", 5, |_| {
+            num_calls += 1;
+            bail!("failed")
+        });
+        assert!(result.is_err());
+        assert_eq!(num_calls, 1);
+    }
+}
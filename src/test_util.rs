@@ -0,0 +1,16 @@
+//! Helpers shared by the tests of several days.
+
+/// Simple linear congruential generator, so that generated test inputs are the same on every run.
+pub(crate) struct Lcg(u32);
+
+impl Lcg {
+    pub(crate) fn new(seed: u32) -> Lcg {
+        Lcg(seed)
+    }
+
+    /// The next pseudo-random number in `0..bound`.
+    pub(crate) fn next_below(&mut self, bound: u32) -> u32 {
+        self.0 = self.0.wrapping_mul(1103515245).wrapping_add(12345);
+        (self.0 >> 16) % bound
+    }
+}
@@ -0,0 +1,168 @@
+//! JavaScript bindings for the solvers, for running them in the browser.
+//!
+//! Build with `cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm`
+//! (or `wasm-pack build -- --no-default-features --features wasm`). Every `solve_dayN` function
+//! takes the puzzle input as a string and returns an object like `{part1: 142, part2: 281}`,
+//! or throws the error message as a string.
+
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day3, day4, day6, day7, day8, day9,
+};
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub fn solve_day1(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day1::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day2(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day2::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day3(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day3::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day4(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day4::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day6(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day6::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day7(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day7::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+/// `part1` is `null` if the network has no `AAA` node.
+#[wasm_bindgen]
+pub fn solve_day8(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day8::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day9(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day9::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day10(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day10::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day11(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day11::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day12(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day12::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day13(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day13::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day14(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day14::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day15(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day15::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day16(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day16::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day17(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day17::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day18(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day18::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day19(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day19::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day20(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day20::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+#[wasm_bindgen]
+pub fn solve_day21(input: &str) -> Result<JsValue, JsValue> {
+    to_js(day21::solve(input).map(|answers| answers_to_json(answers.part1, answers.part2)))
+}
+
+/// Builds the object that is handed to JavaScript, like `{"part1": 142, "part2": 281}`.
+fn answers_to_json(part1: impl Into<Value>, part2: impl Into<Value>) -> Value {
+    json!({
+        "part1": part1.into(),
+        "part2": part2.into(),
+    })
+}
+
+/// Formats an error with its full context, like the command line interface does.
+fn error_message(err: &anyhow::Error) -> String {
+    format!("{:#}", err)
+}
+
+fn to_js(result: anyhow::Result<Value>) -> Result<JsValue, JsValue> {
+    match result {
+        Ok(answers) => js_sys::JSON::parse(&answers.to_string()),
+        Err(err) => Err(JsValue::from_str(&error_message(&err))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_answers_to_json() {
+        let answers = day1::solve("1abc2\ntreb7uchet\n").unwrap();
+        assert_eq!(
+            answers_to_json(answers.part1, answers.part2),
+            json!({"part1": 89, "part2": 89})
+        );
+
+        let answers = day9::solve("0 3 6 9 12 15\n").unwrap();
+        assert_eq!(
+            answers_to_json(answers.part1, answers.part2).to_string(),
+            r#"{"part1":18,"part2":-3}"#
+        );
+
+        assert_eq!(
+            answers_to_json(None::<u64>, 6),
+            json!({"part1": null, "part2": 6})
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        let err = day1::solve("abc").unwrap_err();
+        assert_eq!(
+            error_message(&err),
+            "Line 1 (contents: `abc`) does not contain any digits"
+        );
+    }
+}